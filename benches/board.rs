@@ -0,0 +1,123 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use minesweeper::{
+    board::Board,
+    board_builder::BoardBuilder,
+    cell::{Cell, VisibleCell},
+    grid::Grid,
+};
+
+const HEIGHT: usize = 1000;
+const WIDTH: usize = 1000;
+const MINES: u32 = 150_000;
+
+fn get_playing_board() -> Board {
+    let mut board = BoardBuilder::new(HEIGHT, WIDTH, MINES)
+        .set_seed(1)
+        .build()
+        .unwrap();
+    board.uncover_cell(0, 0);
+    board
+}
+
+fn find_covered_safe_cell(board: &Board) -> (usize, usize) {
+    for y in 0..board.get_height() {
+        for x in 0..board.get_width() {
+            if board.get_visible_cell(x, y) == VisibleCell::Covered
+                && board.get_cell(x, y) != Cell::Mine
+            {
+                return (x, y);
+            }
+        }
+    }
+
+    panic!("No covered safe cell left");
+}
+
+fn to_nested(board: &Board) -> (Vec<Vec<VisibleCell>>, Vec<Vec<Cell>>) {
+    let visible_cells = (0..board.get_height())
+        .map(|y| {
+            (0..board.get_width())
+                .map(|x| board.get_visible_cell(x, y))
+                .collect()
+        })
+        .collect();
+    let cells = (0..board.get_height())
+        .map(|y| {
+            (0..board.get_width())
+                .map(|x| board.get_cell(x, y))
+                .collect()
+        })
+        .collect();
+
+    (visible_cells, cells)
+}
+
+// Win check used before the flat grid: walks every cell after each click.
+fn is_everything_uncovered(visible_cells: &[Vec<VisibleCell>], cells: &[Vec<Cell>]) -> bool {
+    for (visible_row, row) in visible_cells.iter().zip(cells) {
+        for (visible_cell, cell) in visible_row.iter().zip(row) {
+            if !matches!(visible_cell, VisibleCell::Uncovered(_)) && *cell != Cell::Mine {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn win_detection(c: &mut Criterion) {
+    let board = get_playing_board();
+    let (x, y) = find_covered_safe_cell(&board);
+    let (mut visible_cells, cells) = to_nested(&board);
+    // Worst case for the scan: the only covered safe cell is the last one.
+    for row in visible_cells.iter_mut() {
+        for visible_cell in row.iter_mut() {
+            *visible_cell = VisibleCell::Uncovered(Cell::Empty);
+        }
+    }
+    visible_cells[HEIGHT - 1][WIDTH - 1] = VisibleCell::Covered;
+
+    let mut group = c.benchmark_group("win_detection_1000x1000");
+    group.sample_size(20);
+    group.bench_function("nested_vec_full_scan", |b| {
+        b.iter(|| is_everything_uncovered(&visible_cells, &cells))
+    });
+    group.bench_function("flat_grid_uncover_cell", |b| {
+        b.iter_batched(
+            || board.clone(),
+            |mut board| board.uncover_cell(x, y),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn cell_iteration(c: &mut Criterion) {
+    let board = get_playing_board();
+    let (_, cells) = to_nested(&board);
+    let mut grid = Grid::new(HEIGHT, WIDTH, Cell::Empty);
+    for (y, row) in cells.iter().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            grid.set(x, y, cell.clone());
+        }
+    }
+
+    let mut group = c.benchmark_group("count_mines_1000x1000");
+    group.sample_size(20);
+    group.bench_function("nested_vec", |b| {
+        b.iter(|| {
+            cells
+                .iter()
+                .flat_map(|row| row.iter())
+                .filter(|cell| **cell == Cell::Mine)
+                .count()
+        })
+    });
+    group.bench_function("flat_grid", |b| {
+        b.iter(|| grid.iter().filter(|cell| **cell == Cell::Mine).count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, win_detection, cell_iteration);
+criterion_main!(benches);
@@ -0,0 +1,421 @@
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{self, Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, ClearType},
+};
+use minesweeper::{
+    cell::VisibleCell,
+    cell_style::{self, Rgb},
+    constants,
+    difficulty::Difficulty,
+    error::MinesweeperError,
+    first_click_policy::FirstClickPolicy,
+    game::Game,
+    metrics,
+    position::Position,
+    replay::ActionKind,
+    settings::Theme,
+    state::{FinishedState, State},
+};
+use std::{
+    io::{self, Stdout, Write},
+    str::FromStr,
+    time::Duration,
+};
+
+const HELP: &str =
+    "arrows/hjkl move  space reveal  f flag  c chord  ? hint  u undo  U redo  r restart  q quit";
+const SETTINGS_HELP: &str =
+    "1-3 preset  s size  m marks  g no guess  p first click  R ranked  t theme";
+const CELL_WIDTH: usize = 3;
+const BOARD_TOP: usize = 2;
+// The gap below the board, then the status, settings, help and message lines.
+const FOOTER_HEIGHT: usize = 7;
+
+struct Tui {
+    game: Game,
+    cursor: Position,
+    // Top left cell shown when the board does not fit in the terminal.
+    scroll: Position,
+    message: Option<String>,
+    size_input: Option<String>,
+    player: String,
+}
+
+// Puts the terminal back even when drawing fails half way.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new(stdout: &mut Stdout) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen).ok();
+        terminal::disable_raw_mode().ok();
+    }
+}
+
+impl Tui {
+    fn new(game: Game) -> Self {
+        Self {
+            game,
+            cursor: Position { x: 0, y: 0 },
+            scroll: Position { x: 0, y: 0 },
+            message: None,
+            size_input: None,
+            player: std::env::var("USER").unwrap_or_else(|_| String::from("Player")),
+        }
+    }
+
+    // Redraws only after input or when the shown time changes, as clearing the
+    // screen on every tick flickers on slow connections.
+    fn run(&mut self, stdout: &mut Stdout) -> io::Result<()> {
+        let mut changed = true;
+        let mut drawn_seconds = 0;
+        loop {
+            let seconds = self.game.get_board().get_elapsed_time().as_secs();
+            if changed || seconds != drawn_seconds {
+                self.draw(stdout)?;
+                changed = false;
+                drawn_seconds = seconds;
+            }
+            if !event::poll(Duration::from_millis(200))? {
+                continue;
+            }
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    if self.size_input.is_some() {
+                        self.handle_size_key(key);
+                    } else if self.handle_key(key) {
+                        return Ok(());
+                    }
+                    self.record_score();
+                    changed = true;
+                }
+                Event::Resize(_, _) => changed = true,
+                _ => {}
+            }
+        }
+    }
+
+    // Returns true when the player wants to quit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let Position { x, y } = self.cursor;
+        self.message = None;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            KeyCode::Char('c') if control => return true,
+            KeyCode::Char('z') if control => self.undo(),
+            KeyCode::Char('y') if control => self.redo(),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(1, 0),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(0, -1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(0, 1),
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                let kind = match self.game.get_board().get_visible_cell(x, y) {
+                    VisibleCell::Uncovered(_) => ActionKind::Chord,
+                    _ => ActionKind::Uncover,
+                };
+                self.play(kind);
+            }
+            KeyCode::Char('f') => self.play(ActionKind::Flag),
+            KeyCode::Char('c') => self.play(ActionKind::Chord),
+            KeyCode::Char('?') => self.game.show_hint(),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('U') => self.redo(),
+            KeyCode::Char('r') => self.game.restart(),
+            KeyCode::Char(number @ '1'..='3') => {
+                let index = number as usize - '1' as usize;
+                self.start_new_board(Difficulty::PRESETS[index]);
+            }
+            KeyCode::Char('s') => self.size_input = Some(String::new()),
+            KeyCode::Char('m') => {
+                self.game.settings.question_marks = !self.game.settings.question_marks;
+                self.start_new_board(self.game.settings.difficulty);
+            }
+            KeyCode::Char('g') => {
+                self.game.settings.no_guess = !self.game.settings.no_guess;
+                self.start_new_board(self.game.settings.difficulty);
+            }
+            KeyCode::Char('p') => {
+                self.game.settings.first_click_policy = match self.game.settings.first_click_policy
+                {
+                    FirstClickPolicy::None => FirstClickPolicy::SafeCell,
+                    FirstClickPolicy::SafeCell => FirstClickPolicy::SafeOpening,
+                    FirstClickPolicy::SafeOpening => FirstClickPolicy::Anywhere,
+                    FirstClickPolicy::Anywhere => FirstClickPolicy::None,
+                };
+                self.start_new_board(self.game.settings.difficulty);
+            }
+            KeyCode::Char('R') => {
+                self.game.settings.ranked = !self.game.settings.ranked;
+                self.start_new_board(self.game.settings.difficulty);
+            }
+            KeyCode::Char('t') => {
+                self.game.settings.theme = match self.game.settings.theme {
+                    Theme::Light => Theme::Dark,
+                    Theme::Dark => Theme::Light,
+                };
+                self.game.save_settings().ok();
+            }
+            _ => {}
+        }
+        false
+    }
+
+    // Reads "height width mines" for a custom board.
+    fn handle_size_key(&mut self, key: KeyEvent) {
+        let input = self.size_input.get_or_insert_with(String::new);
+        match key.code {
+            KeyCode::Esc => self.size_input = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(character) if character.is_ascii_digit() || character == ' ' => {
+                input.push(character)
+            }
+            KeyCode::Enter => {
+                let values: Vec<&str> = input.split_whitespace().collect();
+                let parsed = match values.as_slice() {
+                    [height, width, mines] => Self::parse("Height", height).and_then(|height| {
+                        let width = Self::parse("Width", width)?;
+                        let mines = Self::parse("Mines", mines)?;
+                        Ok(Difficulty::from_dimensions(height, width, mines))
+                    }),
+                    _ => Err(String::from("Enter height, width and mines")),
+                };
+                self.size_input = None;
+                match parsed {
+                    Ok(difficulty) => self.start_new_board(difficulty),
+                    Err(err) => self.message = Some(err),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn parse<T: FromStr>(field: &'static str, text: &str) -> Result<T, String> {
+        text.parse()
+            .map_err(|_| MinesweeperError::InvalidInput(field).to_string())
+    }
+
+    fn move_cursor(&mut self, dx: isize, dy: isize) {
+        let board = self.game.get_board();
+        let x = self.cursor.x as isize + dx;
+        let y = self.cursor.y as isize + dy;
+        if (0..board.get_width() as isize).contains(&x)
+            && (0..board.get_height() as isize).contains(&y)
+        {
+            self.cursor = Position {
+                x: x as usize,
+                y: y as usize,
+            };
+        }
+    }
+
+    fn play(&mut self, kind: ActionKind) {
+        let Position { x, y } = self.cursor;
+        if let Err(err) = self.game.play(kind, x, y) {
+            self.message = Some(err.to_string());
+        }
+    }
+
+    fn undo(&mut self) {
+        if !self.game.undo() {
+            self.message = Some(String::from(if self.game.settings.ranked {
+                "Undo is disabled in ranked games"
+            } else {
+                "Nothing to undo"
+            }));
+        }
+    }
+
+    fn redo(&mut self) {
+        if !self.game.redo() {
+            self.message = Some(String::from("Nothing to redo"));
+        }
+    }
+
+    fn start_new_board(&mut self, difficulty: Difficulty) {
+        match self.game.start_new_board(difficulty) {
+            Ok(()) => self.cursor = Position { x: 0, y: 0 },
+            Err(err) => self.message = Some(err.to_string()),
+        }
+    }
+
+    fn record_score(&mut self) {
+        match self.game.record_score(&self.player) {
+            Ok(Some(rank)) if rank <= constants::HIGH_SCORES_SHOWN => {
+                self.message = Some(format!("New best time, rank {}", rank));
+            }
+            Ok(_) => {}
+            Err(err) => self.message = Some(err.to_string()),
+        }
+    }
+
+    // Scrolls the board just enough to keep the cursor in the terminal.
+    fn follow_cursor(&mut self, columns: usize, rows: usize) -> (usize, usize) {
+        let board = self.game.get_board();
+        let width = (columns / CELL_WIDTH).clamp(1, board.get_width());
+        let height = rows
+            .saturating_sub(BOARD_TOP + FOOTER_HEIGHT)
+            .clamp(1, board.get_height());
+        self.scroll = Position {
+            x: get_scroll(self.scroll.x, self.cursor.x, width, board.get_width()),
+            y: get_scroll(self.scroll.y, self.cursor.y, height, board.get_height()),
+        };
+
+        (width, height)
+    }
+
+    fn draw(&mut self, stdout: &mut Stdout) -> io::Result<()> {
+        let (columns, rows) = terminal::size()?;
+        let columns = columns as usize;
+        let (width, height) = self.follow_cursor(columns, rows as usize);
+        let board = self.game.get_board();
+        let mut header = format!(
+            "Mines: {}    Time: {}    Clicks: {}",
+            board.get_mines_number(),
+            board.get_elapsed_time().as_secs(),
+            board.get_counters().get_total_clicks()
+        );
+        if width < board.get_width() || height < board.get_height() {
+            header.push_str(&format!(
+                "    Cursor: {},{}",
+                self.cursor.x + 1,
+                self.cursor.y + 1
+            ));
+        }
+        queue!(
+            stdout,
+            terminal::Clear(ClearType::All),
+            cursor::MoveTo(0, 0),
+            Print(clip(&header, columns)),
+        )?;
+
+        let covered_background = cell_style::get_cell_fill(self.game.settings.theme, false);
+        for (row, y) in (self.scroll.y..self.scroll.y + height).enumerate() {
+            queue!(stdout, cursor::MoveTo(0, (BOARD_TOP + row) as u16))?;
+            for x in self.scroll.x..self.scroll.x + width {
+                let position = Position { x, y };
+                let visible_cell = board.get_visible_cell(x, y);
+                let background = if self.game.get_hint() == Some(position) {
+                    Some(constants::HINT_YELLOW)
+                } else if matches!(visible_cell, VisibleCell::Uncovered(_)) {
+                    None
+                } else {
+                    Some(covered_background)
+                };
+                if let Some(background) = background {
+                    queue!(stdout, SetBackgroundColor(to_color(background)))?;
+                }
+                if self.cursor == position {
+                    queue!(stdout, SetAttribute(Attribute::Reverse))?;
+                }
+                queue!(
+                    stdout,
+                    SetForegroundColor(to_color(cell_style::get_cell_text_color(&visible_cell))),
+                    Print(format!(
+                        " {} ",
+                        cell_style::get_cell_text(&visible_cell, &cell_style::ASCII_SYMBOLS)
+                    )),
+                    SetAttribute(Attribute::Reset),
+                    style::ResetColor,
+                )?;
+            }
+        }
+
+        let mut line = (BOARD_TOP + height + 1) as u16;
+        let status = match board.get_state() {
+            State::Finished(FinishedState::Won) => Some("You have won!"),
+            State::Finished(FinishedState::Lost) => Some("You have lost!"),
+            _ => None,
+        };
+        if let Some(status) = status {
+            let mut text = String::from(status);
+            if let Some(metrics) = metrics::calculate(board) {
+                text.push_str(&format!(
+                    "  Time: {:.2} s  3BV: {}/{}  3BV/s: {:.2}",
+                    metrics.seconds, metrics.solved_bbbv, metrics.bbbv, metrics.bbbv_per_second
+                ));
+            }
+            queue!(stdout, cursor::MoveTo(0, line), Print(clip(&text, columns)))?;
+            line += 1;
+        }
+        let settings = &self.game.settings;
+        queue!(
+            stdout,
+            cursor::MoveTo(0, line),
+            Print(clip(
+                &format!(
+                    "{}  marks: {}  no guess: {}  first click: {:?}  ranked: {}",
+                    settings.difficulty.get_name(),
+                    on_off(settings.question_marks),
+                    on_off(settings.no_guess),
+                    settings.first_click_policy,
+                    on_off(settings.ranked)
+                ),
+                columns
+            )),
+            cursor::MoveTo(0, line + 1),
+            Print(clip(HELP, columns)),
+            cursor::MoveTo(0, line + 2),
+            Print(clip(SETTINGS_HELP, columns)),
+        )?;
+        if let Some(input) = &self.size_input {
+            queue!(
+                stdout,
+                cursor::MoveTo(0, line + 4),
+                Print(clip(&format!("Height width mines: {}_", input), columns))
+            )?;
+        } else if let Some(message) = &self.message {
+            queue!(
+                stdout,
+                cursor::MoveTo(0, line + 4),
+                Print(clip(message, columns))
+            )?;
+        }
+
+        stdout.flush()
+    }
+}
+
+// Moves the first shown cell only when the cursor leaves the visible cells.
+fn get_scroll(scroll: usize, cursor: usize, visible: usize, total: usize) -> usize {
+    scroll
+        .min(cursor)
+        .max((cursor + 1).saturating_sub(visible))
+        .min(total - visible)
+}
+
+// Keeps lines from wrapping onto the ones below.
+fn clip(text: &str, columns: usize) -> String {
+    text.chars().take(columns).collect()
+}
+
+fn to_color((r, g, b): Rgb) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let mut tui = Tui::new(Game::load_or_default());
+    let _guard = TerminalGuard::new(&mut stdout)?;
+    tui.run(&mut stdout)
+}
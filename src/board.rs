@@ -1,141 +1,459 @@
 use crate::cell::{Cell, VisibleCell};
+use crate::clock::{Clock, SystemTimeSource, TimeSource};
 use crate::constants;
-use crate::memento::{BoardMemento, Originator};
+use crate::counters::MoveCounters;
+use crate::error::MinesweeperError;
+use crate::first_click_policy::FirstClickPolicy;
+use crate::grid::Grid;
+use crate::memento::{BoardDelta, BoardMemento, CellChange, Journal, Originator};
+use crate::move_outcome::{FlagChange, MoveOutcome};
+use crate::observer::BoardObserver;
+use crate::persistence::{self, PersistenceError};
 use crate::position::Position;
+use crate::probability;
+use crate::solver::{self, Hint};
 use crate::state::{FinishedState, State};
 use rand::rngs::StdRng;
-use rand::{Rng, RngCore, SeedableRng};
+use rand::seq::SliceRandom;
+use rand::{RngCore, SeedableRng};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct Board {
     pub seed: Option<u64>,
+    pub question_marks: bool,
+    pub first_click_policy: FirstClickPolicy,
+    pub no_guess: bool,
+    pub no_guess_attempts: u32,
     state: State,
-    height: u8,
-    width: u8,
-    initial_mines: u8,
-    mines: u8,
-    visible_cells: Vec<Vec<VisibleCell>>,
-    cells: Vec<Vec<Cell>>,
+    height: usize,
+    width: usize,
+    initial_mines: u32,
+    mines: u32,
+    uncovered_cells: usize,
+    visible_cells: Grid<VisibleCell>,
+    cells: Grid<Cell>,
+    clock: Clock,
+    counters: MoveCounters,
+    time_source: Rc<dyn TimeSource>,
+    observers: Vec<Rc<RefCell<dyn BoardObserver>>>,
+    journal: Journal,
 }
 
 impl Board {
-    pub fn new(height: u8, width: u8, mines: u8) -> Self {
-        let cells = vec![vec![Cell::Empty; width as usize]; height as usize];
-        let visible_cells = vec![vec![VisibleCell::Covered; width as usize]; height as usize];
+    pub fn new(height: usize, width: usize, mines: u32) -> Self {
+        let cells = Grid::new(height, width, Cell::Empty);
+        let visible_cells = Grid::new(height, width, VisibleCell::Covered);
 
         Self {
             seed: None,
+            question_marks: true,
+            first_click_policy: FirstClickPolicy::default(),
+            no_guess: false,
+            no_guess_attempts: constants::DEFAULT_NO_GUESS_ATTEMPTS,
             state: State::New,
             height,
             width,
             initial_mines: mines,
             mines,
+            uncovered_cells: 0,
             visible_cells,
             cells,
+            clock: Clock::default(),
+            counters: MoveCounters::default(),
+            time_source: Rc::new(SystemTimeSource::default()),
+            observers: vec![],
+            journal: Journal::default(),
         }
     }
 
     pub fn reset(&mut self) {
-        *self = Self::new(self.height, self.width, self.initial_mines);
+        let board = Self::new(self.height, self.width, self.initial_mines);
+        let previous = std::mem::replace(self, board);
+        self.time_source = previous.time_source;
+        self.observers = previous.observers;
+        self.question_marks = previous.question_marks;
+        self.first_click_policy = previous.first_click_policy;
+        self.no_guess = previous.no_guess;
+        self.no_guess_attempts = previous.no_guess_attempts;
+        self.journal = previous.journal;
+        self.journal
+            .record_replaced(&previous.cells, &self.cells, CellChange::Hidden);
+        self.journal.record_replaced(
+            &previous.visible_cells,
+            &self.visible_cells,
+            CellChange::Visible,
+        );
     }
 
-    pub fn uncover_cell(&mut self, x: u8, y: u8) {
+    pub fn uncover_cell(&mut self, x: usize, y: usize) -> MoveOutcome {
+        self.try_uncover(x, y)
+            .unwrap_or_else(|_| MoveOutcome::new(self.state.clone()))
+    }
+
+    pub fn try_uncover(&mut self, x: usize, y: usize) -> Result<MoveOutcome, MinesweeperError> {
+        self.check_move(x, y)?;
+        match self.get_visible_cell(x, y) {
+            VisibleCell::Uncovered(_) => return Err(MinesweeperError::AlreadyRevealed),
+            VisibleCell::Flagged => return Err(MinesweeperError::CellFlagged),
+            _ => {}
+        }
+        let mut outcome = MoveOutcome::new(self.state.clone());
         if self.state == State::New {
-            self.fill_cells(x, y);
+            self.fill_cells(x, y)?;
             self.state = State::Playing;
-        } else if self.state != State::Playing
-            || self.get_visible_cell(x, y) == VisibleCell::Flagged
-        {
-            return;
+            self.clock.start(self.time_source.now());
         }
+        self.counters.left_clicks += 1;
 
         self.set_cell_visible(x, y);
+        outcome.revealed.push(Position { x, y });
         if self.get_cell(x, y) == Cell::Empty {
-            self.uncover_empty_cells(x, y);
+            outcome.revealed.extend(self.uncover_empty_cells(x, y));
         }
 
         self.check_for_end_of_game(x, y);
+        if self.get_cell(x, y) == Cell::Mine {
+            outcome.mine_hit = Some(Position { x, y });
+        }
+        outcome.state = self.state.clone();
+        self.finish_move(&outcome);
+        Ok(outcome)
+    }
+
+    pub fn flag_cell(&mut self, x: usize, y: usize) -> MoveOutcome {
+        self.try_flag(x, y)
+            .unwrap_or_else(|_| MoveOutcome::new(self.state.clone()))
+    }
+
+    pub fn try_flag(&mut self, x: usize, y: usize) -> Result<MoveOutcome, MinesweeperError> {
+        self.check_move(x, y)?;
+        if self.state == State::New {
+            return Err(MinesweeperError::NotStarted);
+        }
+
+        let previous_visible_cell = self.get_visible_cell(x, y);
+        match previous_visible_cell {
+            VisibleCell::Covered if self.mines > 0 => {
+                self.set_visible_cell(x, y, VisibleCell::Flagged);
+                self.mines -= 1;
+            }
+            VisibleCell::Covered if self.question_marks => {
+                self.set_visible_cell(x, y, VisibleCell::Questioned);
+            }
+            VisibleCell::Flagged => {
+                let visible_cell = if self.question_marks {
+                    VisibleCell::Questioned
+                } else {
+                    VisibleCell::Covered
+                };
+                self.set_visible_cell(x, y, visible_cell);
+                self.mines += 1;
+            }
+            VisibleCell::Questioned => {
+                self.set_visible_cell(x, y, VisibleCell::Covered);
+            }
+            VisibleCell::Uncovered(_) => return Err(MinesweeperError::AlreadyRevealed),
+            _ => {}
+        }
+
+        self.counters.right_clicks += 1;
+        let mut outcome = MoveOutcome::new(self.state.clone());
+        let visible_cell = self.get_visible_cell(x, y);
+        if visible_cell != previous_visible_cell {
+            outcome.flag_change = Some(FlagChange {
+                position: Position { x, y },
+                from: previous_visible_cell,
+                to: visible_cell,
+            });
+        }
+        self.finish_move(&outcome);
+        Ok(outcome)
+    }
+
+    pub fn chord_cell(&mut self, x: usize, y: usize) -> MoveOutcome {
+        self.try_chord(x, y)
+            .unwrap_or_else(|_| MoveOutcome::new(self.state.clone()))
+    }
+
+    pub fn try_chord(&mut self, x: usize, y: usize) -> Result<MoveOutcome, MinesweeperError> {
+        self.check_move(x, y)?;
+        if self.state == State::New {
+            return Err(MinesweeperError::NotStarted);
+        }
+
+        let mut outcome = MoveOutcome::new(self.state.clone());
+        self.uncover_around_clue(x, y, &mut outcome);
+        // A chord that opens nothing is not counted as a click.
+        if !outcome.revealed.is_empty() {
+            self.counters.chords += 1;
+        }
+        outcome.state = self.state.clone();
+        self.finish_move(&outcome);
+        Ok(outcome)
     }
 
-    pub fn flag_cell(&mut self, x: u8, y: u8) {
-        if self.state != State::Playing {
+    fn uncover_around_clue(&mut self, x: usize, y: usize, outcome: &mut MoveOutcome) {
+        let clue = match self.get_visible_cell(x, y) {
+            VisibleCell::Uncovered(Cell::Clue(clue)) => clue,
+            _ => return,
+        };
+
+        let adjacent_flags = self
+            .get_adjacent_positions(x, y)
+            .filter(|position| {
+                self.get_visible_cell(position.x, position.y) == VisibleCell::Flagged
+            })
+            .count();
+        if adjacent_flags != clue as usize {
             return;
         }
 
-        if self.get_visible_cell(x, y) == VisibleCell::Covered && self.mines > 0 {
-            self.visible_cells[y as usize][x as usize] = VisibleCell::Flagged;
-            self.mines -= 1;
-        } else if self.get_visible_cell(x, y) == VisibleCell::Flagged {
-            self.visible_cells[y as usize][x as usize] = VisibleCell::Covered;
-            self.mines += 1;
+        let adjacent_positions: Vec<Position> = self.get_adjacent_positions(x, y).collect();
+        for adjacent_position in adjacent_positions {
+            let Position {
+                x: adjacent_x,
+                y: adjacent_y,
+            } = adjacent_position;
+
+            if !matches!(
+                self.get_visible_cell(adjacent_x, adjacent_y),
+                VisibleCell::Covered | VisibleCell::Questioned
+            ) {
+                continue;
+            }
+
+            self.set_cell_visible(adjacent_x, adjacent_y);
+            outcome.revealed.push(adjacent_position);
+            if self.get_cell(adjacent_x, adjacent_y) == Cell::Empty {
+                outcome
+                    .revealed
+                    .extend(self.uncover_empty_cells(adjacent_x, adjacent_y));
+            }
+
+            self.check_for_end_of_game(adjacent_x, adjacent_y);
+            if self.get_cell(adjacent_x, adjacent_y) == Cell::Mine {
+                outcome.mine_hit = Some(adjacent_position);
+            }
+            if self.state != State::Playing {
+                break;
+            }
         }
     }
 
-    fn check_for_end_of_game(&mut self, x: u8, y: u8) {
+    fn finish_move(&mut self, outcome: &MoveOutcome) {
+        if !outcome.is_empty() {
+            self.counters.effective_clicks += 1;
+        }
+        if let State::Finished(_) = self.state {
+            self.clock.stop(self.time_source.now());
+        }
+        self.notify_observers(outcome);
+    }
+
+    pub fn set_time_source(&mut self, time_source: Rc<dyn TimeSource>) {
+        self.time_source = time_source;
+    }
+
+    pub(crate) fn get_current_time(&self) -> Duration {
+        self.time_source.now()
+    }
+
+    pub fn get_elapsed_time(&self) -> Duration {
+        self.clock.elapsed(self.time_source.now())
+    }
+
+    pub fn get_counters(&self) -> MoveCounters {
+        self.counters
+    }
+
+    pub fn add_observer(&mut self, observer: Rc<RefCell<dyn BoardObserver>>) {
+        self.observers.push(observer);
+    }
+
+    pub fn remove_observers(&mut self) {
+        self.observers.clear();
+    }
+
+    fn notify_observers(&self, outcome: &MoveOutcome) {
+        for observer in self.observers.iter() {
+            let mut observer = observer.borrow_mut();
+            if outcome.previous_state == State::New && outcome.state != State::New {
+                observer.on_game_started();
+            }
+            for position in outcome.revealed.iter() {
+                observer.on_cell_revealed(*position, &self.get_cell(position.x, position.y));
+            }
+            if let Some(flag_change) = &outcome.flag_change {
+                observer.on_flag_changed(flag_change);
+            }
+            if outcome.is_state_changed() {
+                match (&outcome.state, outcome.mine_hit) {
+                    (State::Finished(FinishedState::Won), _) => observer.on_game_won(),
+                    (State::Finished(FinishedState::Lost), Some(mine)) => {
+                        observer.on_game_lost(mine)
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    pub fn hint(&self) -> Hint {
+        solver::hint(&self.visible_cells, self.initial_mines)
+    }
+
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<(), PersistenceError> {
+        persistence::save(self, path.as_ref())
+    }
+
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Board, PersistenceError> {
+        persistence::load(path.as_ref())
+    }
+
+    pub fn get_mine_probabilities(&self) -> Option<Grid<Option<f64>>> {
+        probability::calculate(&self.visible_cells, self.mines)
+    }
+
+    fn check_move(&self, x: usize, y: usize) -> Result<(), MinesweeperError> {
+        if !self.cells.contains(x, y) {
+            return Err(MinesweeperError::OutOfBounds { x, y });
+        }
+        if let State::Finished(_) = self.state {
+            return Err(MinesweeperError::GameOver);
+        }
+
+        Ok(())
+    }
+
+    fn check_for_end_of_game(&mut self, x: usize, y: usize) {
         if self.get_cell(x, y) == Cell::Mine {
             self.state = State::Finished(FinishedState::Lost);
-        } else if self.is_everything_uncovered() {
+        } else if self.uncovered_cells + self.initial_mines as usize == self.cells.len() {
             self.state = State::Finished(FinishedState::Won);
         }
     }
 
-    fn fill_cells(&mut self, starting_x: u8, starting_y: u8) {
+    fn fill_cells(&mut self, starting_x: usize, starting_y: usize) -> Result<(), MinesweeperError> {
+        // Unseeded games pick a seed now so finished games can be reproduced.
+        let seed = *self.seed.get_or_insert_with(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
         let starting_positions = self.get_starting_positions(starting_x, starting_y);
-        self.generate_mines(starting_positions);
-    }
+        // The policy can be changed after the board was built, so the mines
+        // are checked again against the cells it keeps free.
+        let safe_cells = match self.first_click_policy {
+            FirstClickPolicy::None => 0,
+            _ => starting_positions.len().max(1),
+        };
+        if self.initial_mines as usize + safe_cells > self.cells.len() {
+            return Err(MinesweeperError::TooManyMines);
+        }
+        let attempts = if self.no_guess {
+            self.no_guess_attempts
+        } else {
+            1
+        };
 
-    fn get_starting_positions(&mut self, starting_x: u8, starting_y: u8) -> Vec<Position> {
-        let mut starting_positions = vec![Position {
-            x: starting_x as i8,
-            y: starting_y as i8,
-        }];
+        let previous_cells = self.cells.clone();
+        for _ in 0..attempts {
+            self.cells = Grid::new(self.height, self.width, Cell::Empty);
+            self.generate_mines(&mut rng, &starting_positions);
+            if self.first_click_policy == FirstClickPolicy::Anywhere {
+                self.relocate_mine(starting_x, starting_y);
+            }
+            self.generate_clues();
 
-        for adjacent_coordinate in constants::ADJACENT_TILE_OFFSETS {
-            let adjacent_x = starting_x as i8 + adjacent_coordinate.x;
-            let adjacent_y = starting_y as i8 + adjacent_coordinate.y;
+            if !self.no_guess || self.is_solvable_from(starting_x, starting_y) {
+                self.journal
+                    .record_replaced(&previous_cells, &self.cells, CellChange::Hidden);
+                return Ok(());
+            }
+        }
 
-            if self.is_valid_coordinate(adjacent_x, adjacent_y) {
-                starting_positions.push(Position {
-                    x: adjacent_x,
-                    y: adjacent_y,
-                });
+        self.cells = previous_cells;
+        Err(MinesweeperError::GenerationFailed)
+    }
+
+    fn is_solvable_from(&self, starting_x: usize, starting_y: usize) -> bool {
+        let mut board = self.clone();
+        board.observers.clear();
+        board.state = State::Playing;
+        board.uncover_cell(starting_x, starting_y);
+
+        while board.state == State::Playing {
+            let deductions = solver::deduce(&board.visible_cells, board.initial_mines);
+            if deductions.safe.is_empty() {
+                break;
+            }
+            for position in deductions.safe {
+                board.uncover_cell(position.x, position.y);
             }
         }
 
-        starting_positions
+        board.state == State::Finished(FinishedState::Won)
     }
 
-    fn generate_mines(&mut self, starting_positions: Vec<Position>) {
-        let mut rng: Box<dyn RngCore> = match self.seed {
-            Some(seed) => Box::new(StdRng::seed_from_u64(seed)),
-            None => Box::new(rand::thread_rng()),
+    fn get_starting_positions(&mut self, starting_x: usize, starting_y: usize) -> Vec<Position> {
+        let starting_position = Position {
+            x: starting_x,
+            y: starting_y,
         };
 
-        for _ in 0..self.mines {
-            let mut x;
-            let mut y;
-            while {
-                x = rng.gen_range(0..self.width);
-                y = rng.gen_range(0..self.height);
+        match self.first_click_policy {
+            FirstClickPolicy::None | FirstClickPolicy::Anywhere => vec![],
+            FirstClickPolicy::SafeCell => vec![starting_position],
+            FirstClickPolicy::SafeOpening => {
+                let mut starting_positions = vec![starting_position];
+                starting_positions.extend(self.get_adjacent_positions(starting_x, starting_y));
+                starting_positions
+            }
+        }
+    }
+
+    fn relocate_mine(&mut self, x: usize, y: usize) {
+        if self.get_cell(x, y) != Cell::Mine {
+            return;
+        }
+
+        let free_index = self.cells.iter().position(|cell| *cell != Cell::Mine);
+        if let Some(index) = free_index {
+            let position = self.cells.position(index);
+            self.set_cell(position.x, position.y, Cell::Mine);
+            self.set_cell(x, y, Cell::Empty);
+        }
+    }
 
-                self.get_cell(x, y) != Cell::Empty
-                    || starting_positions
-                        .iter()
-                        .find(|position| position.x == x as i8 && position.y == y as i8)
-                        .is_some()
-            } {}
-            self.set_cell(x, y, Cell::Mine);
+    fn generate_mines(&mut self, rng: &mut dyn RngCore, starting_positions: &[Position]) {
+        let mut eligible_indices: Vec<usize> = (0..self.cells.len())
+            .filter(|index| !starting_positions.contains(&self.cells.position(*index)))
+            .collect();
+        let (mine_indices, _) = eligible_indices.partial_shuffle(rng, self.initial_mines as usize);
+        for index in mine_indices.iter() {
+            let position = self.cells.position(*index);
+            self.set_cell(position.x, position.y, Cell::Mine);
         }
     }
 
-    fn generate_cell_clue(&mut self, x: u8, y: u8) {
+    fn generate_clues(&mut self) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.generate_cell_clue(x, y);
+            }
+        }
+    }
+
+    fn generate_cell_clue(&mut self, x: usize, y: usize) {
         if self.get_cell(x, y) != Cell::Empty {
             return;
         }
 
-        let adjacent_mines = constants::ADJACENT_TILE_OFFSETS
-            .iter()
-            .filter(|offset| self.is_mine(x as i8 + offset.x, y as i8 + offset.y))
+        let adjacent_mines = self
+            .get_adjacent_positions(x, y)
+            .filter(|position| self.get_cell(position.x, position.y) == Cell::Mine)
             .count() as u8;
 
         if adjacent_mines > 0 {
@@ -143,81 +461,100 @@ impl Board {
         }
     }
 
-    fn uncover_empty_cells(&mut self, x: u8, y: u8) {
-        for adjacent_coordinate in constants::ADJACENT_TILE_OFFSETS {
-            let adjacent_x = x as i8 + adjacent_coordinate.x;
-            let adjacent_y = y as i8 + adjacent_coordinate.y;
-
-            if !self.is_valid_coordinate(adjacent_x, adjacent_y) {
-                continue;
-            }
+    fn uncover_empty_cells(&mut self, x: usize, y: usize) -> Vec<Position> {
+        let mut revealed = vec![];
+        let mut queue = VecDeque::from(vec![Position { x, y }]);
+
+        while let Some(position) = queue.pop_front() {
+            for offset in constants::ADJACENT_TILE_OFFSETS.iter() {
+                let adjacent_position = match position.offset(offset) {
+                    Some(adjacent) if self.cells.contains(adjacent.x, adjacent.y) => adjacent,
+                    _ => continue,
+                };
+                let Position {
+                    x: adjacent_x,
+                    y: adjacent_y,
+                } = adjacent_position;
 
-            let adjacent_x = adjacent_x as u8;
-            let adjacent_y = adjacent_y as u8;
+                if matches!(
+                    self.get_visible_cell(adjacent_x, adjacent_y),
+                    VisibleCell::Uncovered(_)
+                ) {
+                    continue;
+                }
 
-            let adjacent_tile_before_visibility = self.get_visible_cell(adjacent_x, adjacent_y);
-            self.set_cell_visible(adjacent_x, adjacent_y);
-            if self.get_cell(adjacent_x, adjacent_y) == Cell::Empty
-                && adjacent_tile_before_visibility == VisibleCell::Covered
-            {
-                self.uncover_empty_cells(adjacent_x, adjacent_y);
+                self.set_cell_visible(adjacent_x, adjacent_y);
+                revealed.push(adjacent_position);
+                if self.get_cell(adjacent_x, adjacent_y) == Cell::Empty {
+                    queue.push_back(adjacent_position);
+                }
             }
         }
+
+        revealed
     }
 
-    fn is_everything_uncovered(&mut self) -> bool {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if self.get_visible_cell(x, y) == VisibleCell::Covered
-                    && self.get_cell(x, y) != Cell::Mine
-                {
-                    return false;
-                }
-            }
-        }
+    pub fn get_cell(&self, x: usize, y: usize) -> Cell {
+        self.cells.get(x, y).clone()
+    }
 
-        true
+    pub fn get_visible_cell(&self, x: usize, y: usize) -> VisibleCell {
+        self.visible_cells.get(x, y).clone()
     }
 
-    pub fn get_cell(&self, x: u8, y: u8) -> Cell {
-        self.cells[y as usize][x as usize].clone()
+    pub fn get_visible_cells(&self) -> &Grid<VisibleCell> {
+        &self.visible_cells
     }
 
-    pub fn get_visible_cell(&self, x: u8, y: u8) -> VisibleCell {
-        self.visible_cells[y as usize][x as usize].clone()
+    pub fn get_cells(&self) -> &Grid<Cell> {
+        &self.cells
     }
 
-    fn set_cell(&mut self, x: u8, y: u8, cell: Cell) {
-        self.cells[y as usize][x as usize] = cell;
+    fn set_cell(&mut self, x: usize, y: usize, cell: Cell) {
+        self.cells.set(x, y, cell);
     }
 
-    fn set_cell_visible(&mut self, x: u8, y: u8) {
-        self.generate_cell_clue(x, y);
-        self.visible_cells[y as usize][x as usize] =
-            VisibleCell::Uncovered(self.get_cell(x, y).clone());
+    fn set_visible_cell(&mut self, x: usize, y: usize, visible_cell: VisibleCell) {
+        let index = self.visible_cells.index(x, y);
+        self.journal
+            .record(CellChange::Visible(index, self.get_visible_cell(x, y)));
+        self.visible_cells.set(x, y, visible_cell);
     }
 
-    fn is_mine(&self, x: i8, y: i8) -> bool {
-        self.is_valid_coordinate(x, y) && self.get_cell(x as u8, y as u8) == Cell::Mine
+    fn set_cell_visible(&mut self, x: usize, y: usize) {
+        let cell = self.get_cell(x, y);
+        let visible_cell = self.get_visible_cell(x, y);
+        if cell != Cell::Mine && !matches!(visible_cell, VisibleCell::Uncovered(_)) {
+            self.uncovered_cells += 1;
+        }
+        // Openings clear wrong flags, which gives them back to the counter.
+        if visible_cell == VisibleCell::Flagged {
+            self.mines += 1;
+        }
+        self.set_visible_cell(x, y, VisibleCell::Uncovered(cell));
     }
 
-    fn is_valid_coordinate(&self, x: i8, y: i8) -> bool {
-        x >= 0 && x < self.width as i8 && y >= 0 && y < self.height as i8
+    fn get_adjacent_positions(&self, x: usize, y: usize) -> impl Iterator<Item = Position> + '_ {
+        self.cells.get_adjacent_positions(x, y)
     }
 
     pub fn get_state(&self) -> &State {
         &self.state
     }
 
-    pub fn get_mines_number(&self) -> u8 {
+    pub fn get_initial_mines_number(&self) -> u32 {
+        self.initial_mines
+    }
+
+    pub fn get_mines_number(&self) -> u32 {
         self.mines
     }
 
-    pub fn get_height(&self) -> u8 {
+    pub fn get_height(&self) -> usize {
         self.height
     }
 
-    pub fn get_width(&self) -> u8 {
+    pub fn get_width(&self) -> usize {
         self.width
     }
 }
@@ -225,23 +562,97 @@ impl Board {
 impl Originator<BoardMemento> for Board {
     fn save_memento(&self) -> Box<BoardMemento> {
         Box::new(BoardMemento {
+            seed: self.seed,
             state: self.state.clone(),
+            question_marks: self.question_marks,
+            first_click_policy: self.first_click_policy,
+            no_guess: self.no_guess,
+            no_guess_attempts: self.no_guess_attempts,
             height: self.height,
             width: self.width,
             initial_mines: self.initial_mines,
             mines: self.mines,
+            uncovered_cells: self.uncovered_cells,
             visible_cells: self.visible_cells.clone(),
             cells: self.cells.clone(),
+            clock: self.clock,
+            counters: self.counters,
         })
     }
 
     fn restore_from_memento(&mut self, memento: Box<BoardMemento>) {
+        if (memento.height, memento.width) == (self.height, self.width) {
+            self.journal
+                .record_replaced(&self.cells, &memento.cells, CellChange::Hidden);
+            self.journal.record_replaced(
+                &self.visible_cells,
+                &memento.visible_cells,
+                CellChange::Visible,
+            );
+        }
+        self.seed = memento.seed;
         self.state = memento.state;
+        self.question_marks = memento.question_marks;
+        self.first_click_policy = memento.first_click_policy;
+        self.no_guess = memento.no_guess;
+        self.no_guess_attempts = memento.no_guess_attempts;
         self.height = memento.height;
         self.width = memento.width;
         self.initial_mines = memento.initial_mines;
         self.mines = memento.mines;
+        self.uncovered_cells = memento.uncovered_cells;
         self.visible_cells = memento.visible_cells;
         self.cells = memento.cells;
+        self.clock = memento.clock;
+        self.counters = memento.counters;
+    }
+}
+
+impl Originator<BoardDelta> for Board {
+    fn save_memento(&self) -> Box<BoardDelta> {
+        Box::new(BoardDelta {
+            seed: self.seed,
+            state: self.state.clone(),
+            question_marks: self.question_marks,
+            first_click_policy: self.first_click_policy,
+            no_guess: self.no_guess,
+            no_guess_attempts: self.no_guess_attempts,
+            initial_mines: self.initial_mines,
+            mines: self.mines,
+            uncovered_cells: self.uncovered_cells,
+            clock: self.clock,
+            counters: self.counters,
+            changes: self.journal.start(),
+        })
+    }
+
+    fn restore_from_memento(&mut self, memento: Box<BoardDelta>) {
+        // Undoing newest first leaves each cell with its oldest value.
+        let changes = memento.changes.take();
+        for change in changes.into_iter().rev() {
+            match change {
+                CellChange::Hidden(index, cell) => {
+                    let Position { x, y } = self.cells.position(index);
+                    self.journal
+                        .record(CellChange::Hidden(index, self.get_cell(x, y)));
+                    self.set_cell(x, y, cell);
+                }
+                CellChange::Visible(index, visible_cell) => {
+                    let Position { x, y } = self.visible_cells.position(index);
+                    self.set_visible_cell(x, y, visible_cell);
+                }
+            }
+        }
+        self.seed = memento.seed;
+        self.state = memento.state;
+        self.question_marks = memento.question_marks;
+        self.first_click_policy = memento.first_click_policy;
+        self.no_guess = memento.no_guess;
+        self.no_guess_attempts = memento.no_guess_attempts;
+        self.initial_mines = memento.initial_mines;
+        self.mines = memento.mines;
+        self.uncovered_cells = memento.uncovered_cells;
+        self.clock = memento.clock;
+        self.counters = memento.counters;
     }
 }
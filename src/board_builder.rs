@@ -1,4 +1,9 @@
 use crate::board::Board;
+use crate::clock::TimeSource;
+use crate::difficulty::Difficulty;
+use crate::error::MinesweeperError;
+use crate::first_click_policy::FirstClickPolicy;
+use std::rc::Rc;
 
 #[derive(Clone)]
 pub struct BoardBuilder {
@@ -6,20 +11,54 @@ pub struct BoardBuilder {
 }
 
 impl BoardBuilder {
-    pub fn new(height: u8, width: u8, mines: u8) -> Self {
+    pub fn new(height: usize, width: usize, mines: u32) -> Self {
         Self {
             board: Board::new(height, width, mines),
         }
     }
+    pub fn from_difficulty(difficulty: Difficulty) -> Self {
+        let (height, width, mines) = difficulty.get_dimensions();
+        Self::new(height, width, mines)
+    }
     pub fn set_seed(&mut self, seed: u64) -> Self {
         self.board.seed = Some(seed);
         self.clone()
     }
-    pub fn build(&self) -> Result<Board, &'static str> {
-        if self.board.get_mines_number() as i16
-            > self.board.get_height() as i16 * self.board.get_width() as i16 - 3 as i16
+    pub fn set_question_marks(&mut self, question_marks: bool) -> Self {
+        self.board.question_marks = question_marks;
+        self.clone()
+    }
+    pub fn set_first_click_policy(&mut self, first_click_policy: FirstClickPolicy) -> Self {
+        self.board.first_click_policy = first_click_policy;
+        self.clone()
+    }
+    pub fn set_no_guess(&mut self, no_guess: bool) -> Self {
+        self.board.no_guess = no_guess;
+        self.clone()
+    }
+    pub fn set_no_guess_attempts(&mut self, attempts: u32) -> Self {
+        self.board.no_guess_attempts = attempts;
+        self.clone()
+    }
+    pub fn set_time_source(&mut self, time_source: Rc<dyn TimeSource>) -> Self {
+        self.board.set_time_source(time_source);
+        self.clone()
+    }
+    pub fn build(&self) -> Result<Board, MinesweeperError> {
+        if self.board.get_height() == 0 || self.board.get_width() == 0 {
+            return Err(MinesweeperError::InvalidDimensions);
+        }
+
+        // With a safe opening the first click clears its whole neighbourhood,
+        // which is largest for a click away from the corners and edges.
+        let safe_zone = self
+            .board
+            .first_click_policy
+            .get_safe_zone_size(self.board.get_height(), self.board.get_width());
+        if self.board.get_mines_number() as usize + safe_zone
+            > self.board.get_height() * self.board.get_width()
         {
-            return Err("Wrong amount of mines in comparison with width and height");
+            return Err(MinesweeperError::TooManyMines);
         }
 
         Ok(self.board.clone())
@@ -30,26 +69,63 @@ impl BoardBuilder {
 mod tests {
     use crate::{
         board_builder::BoardBuilder,
-        memento::{Caretaker, Originator},
+        cell::{Cell, VisibleCell},
+        clock::ManualTimeSource,
+        difficulty::Difficulty,
+        error::MinesweeperError,
+        first_click_policy::FirstClickPolicy,
+        memento::{BoardDelta, BoardMemento, Caretaker, Originator},
+        move_outcome::FlagChange,
+        observer::BoardObserver,
+        position::Position,
+        solver,
         state::{FinishedState, State},
     };
+    use std::{cell::RefCell, rc::Rc, time::Duration};
 
     fn get_test_builder() -> BoardBuilder {
-        BoardBuilder::new(3, 3, 2)
+        BoardBuilder::new(3, 4, 2)
+    }
+
+    #[derive(Default)]
+    struct EventRecorder {
+        events: Vec<String>,
+    }
+
+    impl BoardObserver for EventRecorder {
+        fn on_game_started(&mut self) {
+            self.events.push(String::from("started"));
+        }
+        fn on_cell_revealed(&mut self, position: Position, _cell: &Cell) {
+            self.events
+                .push(format!("revealed {} {}", position.x, position.y));
+        }
+        fn on_flag_changed(&mut self, flag_change: &FlagChange) {
+            self.events.push(format!(
+                "flag {} {}",
+                flag_change.position.x, flag_change.position.y
+            ));
+        }
+        fn on_game_won(&mut self) {
+            self.events.push(String::from("won"));
+        }
+        fn on_game_lost(&mut self, mine: Position) {
+            self.events.push(format!("lost {} {}", mine.x, mine.y));
+        }
     }
 
     #[test]
     fn wins_game() {
         /*
-           SEED 1
-           1_🚩
-           __2
-           __🚩
+           SEED 12
+           1__2
+           ___🚩
+           ___🚩
         */
-        let mut board = get_test_builder().set_seed(1).build().unwrap();
+        let mut board = get_test_builder().set_seed(12).build().unwrap();
 
         board.uncover_cell(0, 0);
-        board.uncover_cell(2, 1);
+        board.uncover_cell(3, 0);
         let state = board.get_state();
         assert_eq!(*state, State::Finished(FinishedState::Won));
     }
@@ -57,15 +133,15 @@ mod tests {
     #[test]
     fn loses_game() {
         /*
-           SEED 2
-           1_🚩
-           _💣_
-           ___
+           SEED 12
+           1___
+           ___💣
+           ___🚩
         */
-        let mut board = get_test_builder().set_seed(2).build().unwrap();
+        let mut board = get_test_builder().set_seed(12).build().unwrap();
 
         board.uncover_cell(0, 0);
-        board.uncover_cell(1, 2);
+        board.uncover_cell(3, 1);
         let state = board.get_state();
         assert_eq!(*state, State::Finished(FinishedState::Lost));
     }
@@ -73,25 +149,737 @@ mod tests {
     #[test]
     fn restores_board_state() {
         /*
-           SEED 3
-           1__
-           __🚩
-           💣__
+           SEED 12
+           1___
+           ___🚩
+           ___💣
         */
-        let mut caretaker = Caretaker::new();
-        let mut board = get_test_builder().set_seed(3).build().unwrap();
+        let mut caretaker = Caretaker::<BoardMemento>::new();
+        let mut board = get_test_builder().set_seed(12).build().unwrap();
         caretaker.add_memento(board.save_memento());
 
         board.uncover_cell(0, 0);
         caretaker.add_memento(board.save_memento());
 
-        board.uncover_cell(0, 2);
+        board.uncover_cell(3, 2);
         let state = board.get_state();
         assert_eq!(*state, State::Finished(FinishedState::Lost));
 
-        board.restore_from_memento(caretaker.get_last_memento().unwrap());
+        assert!(caretaker.undo(&mut board));
 
         let state = board.get_state();
         assert_eq!(*state, State::Playing);
     }
+
+    #[test]
+    fn wins_game_by_chording() {
+        /*
+           SEED 1
+           ____
+           ____
+           🚩_🚩_
+        */
+        let mut board = get_test_builder().set_seed(1).build().unwrap();
+
+        board.uncover_cell(0, 0);
+        board.flag_cell(0, 2);
+        board.chord_cell(0, 1);
+        assert_eq!(*board.get_state(), State::Playing);
+
+        board.flag_cell(2, 2);
+        board.chord_cell(3, 1);
+        let state = board.get_state();
+        assert_eq!(*state, State::Finished(FinishedState::Won));
+    }
+
+    #[test]
+    fn loses_game_by_chording_with_wrong_flag() {
+        /*
+           SEED 1
+           ____
+           ____
+           💣_🚩_
+        */
+        let mut board = get_test_builder().set_seed(1).build().unwrap();
+
+        board.uncover_cell(0, 0);
+        board.flag_cell(1, 2);
+        board.chord_cell(0, 1);
+        let state = board.get_state();
+        assert_eq!(*state, State::Finished(FinishedState::Lost));
+    }
+
+    #[test]
+    fn ignores_chord_when_flags_do_not_match_clue() {
+        let mut board = get_test_builder().set_seed(1).build().unwrap();
+
+        board.uncover_cell(0, 0);
+        board.flag_cell(0, 2);
+        board.chord_cell(1, 1);
+        assert_eq!(*board.get_state(), State::Playing);
+        assert!(board.get_visible_cell(1, 2) == VisibleCell::Covered);
+    }
+
+    #[test]
+    fn reports_invalid_chords() {
+        let mut board = get_test_builder().set_seed(1).build().unwrap();
+        assert_eq!(board.try_chord(0, 1), Err(MinesweeperError::NotStarted));
+
+        board.uncover_cell(0, 0);
+        assert_eq!(
+            board.try_chord(10, 10),
+            Err(MinesweeperError::OutOfBounds { x: 10, y: 10 })
+        );
+        assert_eq!(*board.get_state(), State::Playing);
+
+        board.flag_cell(1, 2);
+        board.try_chord(0, 1).unwrap();
+        assert_eq!(board.try_chord(0, 1), Err(MinesweeperError::GameOver));
+    }
+
+    #[test]
+    fn cycles_through_flag_and_question_mark() {
+        let mut board = get_test_builder().set_seed(2).build().unwrap();
+        board.uncover_cell(0, 0);
+
+        board.flag_cell(2, 2);
+        assert!(board.get_visible_cell(2, 2) == VisibleCell::Flagged);
+        assert_eq!(board.get_mines_number(), 1);
+
+        board.flag_cell(2, 2);
+        assert!(board.get_visible_cell(2, 2) == VisibleCell::Questioned);
+        assert_eq!(board.get_mines_number(), 2);
+
+        board.flag_cell(2, 2);
+        assert!(board.get_visible_cell(2, 2) == VisibleCell::Covered);
+        assert_eq!(board.get_mines_number(), 2);
+    }
+
+    #[test]
+    fn skips_question_mark_when_disabled() {
+        let mut board = get_test_builder()
+            .set_seed(2)
+            .set_question_marks(false)
+            .build()
+            .unwrap();
+        board.uncover_cell(0, 0);
+
+        board.flag_cell(2, 2);
+        board.flag_cell(2, 2);
+        assert!(board.get_visible_cell(2, 2) == VisibleCell::Covered);
+    }
+
+    #[test]
+    fn redoes_undone_moves() {
+        let mut caretaker = Caretaker::<BoardMemento>::with_max_depth(2);
+        let mut board = get_test_builder().set_seed(12).build().unwrap();
+        assert!(!caretaker.undo(&mut board));
+
+        caretaker.add_memento(board.save_memento());
+        board.uncover_cell(0, 0);
+        caretaker.add_memento(board.save_memento());
+        board.flag_cell(3, 1);
+        caretaker.add_memento(board.save_memento());
+        board.uncover_cell(3, 0);
+
+        assert!(caretaker.undo(&mut board));
+        assert_eq!(board.get_visible_cell(3, 0), VisibleCell::Covered);
+        assert!(caretaker.undo(&mut board));
+        assert_eq!(board.get_visible_cell(3, 1), VisibleCell::Covered);
+        assert!(!caretaker.undo(&mut board));
+        assert_eq!(*board.get_state(), State::Playing);
+
+        assert!(caretaker.redo(&mut board));
+        assert!(caretaker.redo(&mut board));
+        assert!(!caretaker.redo(&mut board));
+        assert_eq!(*board.get_state(), State::Finished(FinishedState::Won));
+
+        assert!(caretaker.undo(&mut board));
+        caretaker.add_memento(board.save_memento());
+        assert!(!caretaker.can_redo());
+    }
+
+    #[test]
+    fn keeps_delta_history_small() {
+        let mut caretaker = Caretaker::<BoardDelta>::with_max_depth(100);
+        let mut board = BoardBuilder::new(50, 50, 300).set_seed(3).build().unwrap();
+        caretaker.add_memento(board.save_memento());
+        board.uncover_cell(25, 25);
+        assert!(caretaker.undo(&mut board));
+        assert_eq!(*board.get_state(), State::New);
+        assert!(board.get_cells().iter().all(|cell| *cell == Cell::Empty));
+        assert!(caretaker.redo(&mut board));
+        assert_eq!(*board.get_state(), State::Playing);
+
+        let covered: Vec<Position> = (0..2500)
+            .map(|index| board.get_visible_cells().position(index))
+            .filter(|position| {
+                board.get_visible_cell(position.x, position.y) == VisibleCell::Covered
+            })
+            .collect();
+        let mut snapshot = None;
+        for move_number in 0..5000 {
+            if move_number == 4900 {
+                snapshot = Some(board.get_visible_cells().clone());
+            }
+            let memento: Box<BoardDelta> = board.save_memento();
+            let position = covered[move_number % covered.len()];
+            board.flag_cell(position.x, position.y);
+            assert!(memento.get_change_count() <= 1);
+            caretaker.add_memento(memento);
+        }
+        let last = board.get_visible_cells().clone();
+
+        while caretaker.undo(&mut board) {}
+        assert_eq!(Some(board.get_visible_cells().clone()), snapshot);
+        while caretaker.redo(&mut board) {}
+        assert_eq!(*board.get_visible_cells(), last);
+    }
+
+    #[test]
+    fn restores_snapshot_after_reset() {
+        let time_source = Rc::new(ManualTimeSource::default());
+        let mut board = get_test_builder()
+            .set_seed(12)
+            .set_time_source(time_source.clone())
+            .build()
+            .unwrap();
+        board.uncover_cell(0, 0);
+        time_source.advance(Duration::from_secs(2));
+        let memento: Box<BoardMemento> = board.save_memento();
+
+        board.reset();
+        board.first_click_policy = FirstClickPolicy::Anywhere;
+        board.no_guess = true;
+        board.uncover_cell(2, 2);
+        board.restore_from_memento(memento);
+
+        assert_eq!(board.seed, Some(12));
+        assert_eq!(board.first_click_policy, FirstClickPolicy::default());
+        assert!(!board.no_guess);
+        time_source.advance(Duration::from_secs(1));
+        assert_eq!(board.get_elapsed_time(), Duration::from_secs(3));
+        board.uncover_cell(3, 0);
+        assert_eq!(*board.get_state(), State::Finished(FinishedState::Won));
+    }
+
+    #[test]
+    fn restores_snapshot_across_board_sizes() {
+        let mut small = get_test_builder().set_seed(12).build().unwrap();
+        let mut expert = BoardBuilder::from_difficulty(Difficulty::Expert)
+            .set_seed(99)
+            .set_first_click_policy(FirstClickPolicy::SafeCell)
+            .build()
+            .unwrap();
+        let expert_memento: Box<BoardMemento> = expert.save_memento();
+        expert.uncover_cell(15, 8);
+
+        small.uncover_cell(0, 0);
+        let small_memento: Box<BoardMemento> = small.save_memento();
+        let small_cells = small.get_visible_cells().clone();
+
+        small.restore_from_memento(expert_memento);
+        assert_eq!((small.get_height(), small.get_width()), (16, 30));
+        assert_eq!(small.seed, Some(99));
+        assert_eq!(*small.get_state(), State::New);
+        small.uncover_cell(15, 8);
+        assert_eq!(small.get_visible_cells(), expert.get_visible_cells());
+
+        expert.restore_from_memento(small_memento);
+        assert_eq!((expert.get_height(), expert.get_width()), (3, 4));
+        assert_eq!(expert.first_click_policy, FirstClickPolicy::default());
+        assert_eq!(*expert.get_visible_cells(), small_cells);
+        expert.uncover_cell(3, 0);
+        assert_eq!(*expert.get_state(), State::Finished(FinishedState::Won));
+    }
+
+    #[test]
+    fn restores_question_marks() {
+        let mut caretaker = Caretaker::<BoardMemento>::new();
+        let mut board = get_test_builder().set_seed(2).build().unwrap();
+        board.uncover_cell(0, 0);
+        board.flag_cell(2, 2);
+        board.flag_cell(2, 2);
+        caretaker.add_memento(board.save_memento());
+
+        board.flag_cell(2, 2);
+        assert!(caretaker.undo(&mut board));
+
+        assert!(board.get_visible_cell(2, 2) == VisibleCell::Questioned);
+        assert_eq!(board.get_mines_number(), 2);
+    }
+
+    #[test]
+    fn places_more_than_255_mines_on_large_board() {
+        let mut board = BoardBuilder::new(100, 200, 5000)
+            .set_seed(1)
+            .build()
+            .unwrap();
+        board.uncover_cell(199, 99);
+
+        let mut mines = 0;
+        for y in 0..board.get_height() {
+            for x in 0..board.get_width() {
+                if board.get_cell(x, y) == Cell::Mine {
+                    mines += 1;
+                }
+            }
+        }
+        assert_eq!(mines, 5000);
+        assert_eq!(*board.get_state(), State::Playing);
+    }
+
+    #[test]
+    fn uncovers_cells_beyond_127_columns() {
+        let mut board = BoardBuilder::new(1, 300, 0).build().unwrap();
+
+        board.uncover_cell(299, 0);
+        assert!(board.get_visible_cell(0, 0) == VisibleCell::Uncovered(Cell::Empty));
+        assert_eq!(*board.get_state(), State::Finished(FinishedState::Won));
+    }
+
+    #[test]
+    fn rejects_too_many_mines() {
+        assert!(BoardBuilder::new(100, 200, 19_992).build().is_err());
+        assert!(BoardBuilder::new(100, 200, 19_991).build().is_ok());
+    }
+
+    #[test]
+    fn wins_game_after_restoring_board_state() {
+        let mut caretaker = Caretaker::<BoardMemento>::new();
+        let mut board = get_test_builder().set_seed(12).build().unwrap();
+
+        board.uncover_cell(0, 0);
+        caretaker.add_memento(board.save_memento());
+        board.uncover_cell(3, 1);
+        assert!(caretaker.undo(&mut board));
+
+        board.uncover_cell(3, 0);
+        let state = board.get_state();
+        assert_eq!(*state, State::Finished(FinishedState::Won));
+    }
+
+    #[test]
+    fn uncovers_large_empty_area_without_recursion() {
+        let mut board = BoardBuilder::new(1000, 1000, 5)
+            .set_seed(1)
+            .build()
+            .unwrap();
+
+        let outcome = board.uncover_cell(500, 500);
+        assert_eq!(outcome.revealed.len(), 1000 * 1000 - 5);
+        assert_eq!(*board.get_state(), State::Finished(FinishedState::Won));
+    }
+
+    #[test]
+    fn reports_revealed_cells() {
+        let mut board = get_test_builder().set_seed(1).build().unwrap();
+
+        let outcome = board.uncover_cell(0, 0);
+        assert_eq!(outcome.revealed.len(), 8);
+        assert!(outcome.revealed.iter().all(|position| matches!(
+            board.get_visible_cell(position.x, position.y),
+            VisibleCell::Uncovered(_)
+        )));
+        assert!(board.uncover_cell(0, 0).is_empty());
+    }
+
+    #[test]
+    fn reports_flag_changes_and_state_transitions() {
+        let mut board = get_test_builder().set_seed(12).build().unwrap();
+
+        let outcome = board.uncover_cell(0, 0);
+        assert_eq!(outcome.previous_state, State::New);
+        assert_eq!(outcome.state, State::Playing);
+        assert_eq!(outcome.mine_hit, None);
+
+        let outcome = board.flag_cell(3, 1);
+        let flag_change = outcome.flag_change.as_ref().unwrap();
+        assert_eq!(flag_change.position, Position { x: 3, y: 1 });
+        assert_eq!(flag_change.from, VisibleCell::Covered);
+        assert_eq!(flag_change.to, VisibleCell::Flagged);
+        assert!(!outcome.is_state_changed());
+
+        let outcome = board.uncover_cell(3, 2);
+        assert_eq!(outcome.mine_hit, Some(Position { x: 3, y: 2 }));
+        assert_eq!(outcome.state, State::Finished(FinishedState::Lost));
+        assert!(outcome.is_state_changed());
+        assert!(board.uncover_cell(3, 0).is_empty());
+    }
+
+    #[test]
+    fn notifies_observers_about_game_events() {
+        let recorder = Rc::new(RefCell::new(EventRecorder::default()));
+        let mut board = get_test_builder().set_seed(12).build().unwrap();
+        board.add_observer(recorder.clone());
+
+        board.uncover_cell(0, 0);
+        board.flag_cell(3, 1);
+        board.uncover_cell(3, 2);
+        assert_eq!(recorder.borrow().events.len(), 13);
+        assert_eq!(recorder.borrow().events[0], "started");
+        assert_eq!(recorder.borrow().events[10], "flag 3 1");
+        assert_eq!(recorder.borrow().events[11], "revealed 3 2");
+        assert_eq!(recorder.borrow().events[12], "lost 3 2");
+
+        recorder.borrow_mut().events.clear();
+        board.reset();
+        board.seed = Some(12);
+        board.uncover_cell(0, 0);
+        board.uncover_cell(3, 0);
+        assert_eq!(recorder.borrow().events.last().unwrap(), "won");
+    }
+
+    #[test]
+    fn times_game_from_first_uncover_until_finished() {
+        let time_source = Rc::new(ManualTimeSource::default());
+        let mut board = get_test_builder()
+            .set_seed(12)
+            .set_time_source(time_source.clone())
+            .build()
+            .unwrap();
+
+        time_source.advance(Duration::from_secs(5));
+        assert_eq!(board.get_elapsed_time(), Duration::ZERO);
+        board.uncover_cell(0, 0);
+        time_source.advance(Duration::from_secs(3));
+        assert_eq!(board.get_elapsed_time(), Duration::from_secs(3));
+        board.uncover_cell(3, 0);
+        time_source.advance(Duration::from_secs(10));
+        assert_eq!(board.get_elapsed_time(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn counts_clicks_and_effective_clicks() {
+        let mut board = get_test_builder().set_seed(1).build().unwrap();
+        board.uncover_cell(0, 0);
+        let mut caretaker = Caretaker::<BoardMemento>::new();
+        caretaker.add_memento(board.save_memento());
+
+        board.flag_cell(0, 2);
+        board.chord_cell(1, 1);
+        board.chord_cell(0, 1);
+        board.flag_cell(2, 2);
+        let counters = board.get_counters();
+        assert_eq!(counters.left_clicks, 1);
+        assert_eq!(counters.right_clicks, 2);
+        assert_eq!(counters.chords, 1);
+        assert_eq!(counters.effective_clicks, 4);
+
+        assert!(caretaker.undo(&mut board));
+        assert_eq!(board.get_counters().get_total_clicks(), 1);
+    }
+
+    #[test]
+    fn generates_clues_with_mines() {
+        /*
+           SEED 1
+           ____
+           1211
+           🚩2🚩1
+        */
+        let mut board = get_test_builder().set_seed(1).build().unwrap();
+        board.uncover_cell(0, 0);
+
+        let expected = [
+            [Cell::Empty, Cell::Empty, Cell::Empty, Cell::Empty],
+            [Cell::Clue(1), Cell::Clue(2), Cell::Clue(1), Cell::Clue(1)],
+            [Cell::Mine, Cell::Clue(2), Cell::Mine, Cell::Clue(1)],
+        ];
+        for (y, row) in expected.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                assert!(board.get_cell(x, y) == *cell);
+            }
+        }
+    }
+
+    #[test]
+    fn generates_clues_matching_adjacent_mines() {
+        for seed in 0..10 {
+            let mut board = BoardBuilder::new(16, 30, 99)
+                .set_seed(seed)
+                .build()
+                .unwrap();
+            board.uncover_cell(15, 8);
+
+            for y in 0..board.get_height() {
+                for x in 0..board.get_width() {
+                    if board.get_cell(x, y) == Cell::Mine {
+                        continue;
+                    }
+
+                    let mut adjacent_mines = 0;
+                    for adjacent_y in y.saturating_sub(1)..=(y + 1).min(board.get_height() - 1) {
+                        for adjacent_x in x.saturating_sub(1)..=(x + 1).min(board.get_width() - 1) {
+                            if board.get_cell(adjacent_x, adjacent_y) == Cell::Mine {
+                                adjacent_mines += 1;
+                            }
+                        }
+                    }
+                    let expected = match adjacent_mines {
+                        0 => Cell::Empty,
+                        adjacent_mines => Cell::Clue(adjacent_mines),
+                    };
+                    assert!(board.get_cell(x, y) == expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_mines_without_room_for_first_click_opening() {
+        assert!(BoardBuilder::new(9, 9, 72).build().is_ok());
+        assert!(BoardBuilder::new(9, 9, 73).build().is_err());
+        assert!(BoardBuilder::new(1, 5, 2).build().is_ok());
+        assert!(BoardBuilder::new(1, 5, 3).build().is_err());
+        assert_eq!(
+            BoardBuilder::new(3, 3, 1).build().err(),
+            Some(MinesweeperError::TooManyMines)
+        );
+        assert_eq!(
+            BoardBuilder::new(0, 3, 0).build().err(),
+            Some(MinesweeperError::InvalidDimensions)
+        );
+    }
+
+    #[test]
+    fn fills_densest_board_for_any_first_click() {
+        for (x, y) in [(0, 0), (4, 0), (8, 8), (4, 4)] {
+            let mut board = BoardBuilder::new(9, 9, 72).set_seed(1).build().unwrap();
+            board.uncover_cell(x, y);
+
+            let mut mines = 0;
+            for y in 0..board.get_height() {
+                for x in 0..board.get_width() {
+                    if board.get_cell(x, y) == Cell::Mine {
+                        mines += 1;
+                    }
+                }
+            }
+            assert_eq!(mines, 72);
+        }
+    }
+
+    #[test]
+    fn wins_densest_board_with_center_click() {
+        let mut board = BoardBuilder::new(9, 9, 72).set_seed(1).build().unwrap();
+
+        board.uncover_cell(4, 4);
+        assert_eq!(*board.get_state(), State::Finished(FinishedState::Won));
+    }
+
+    #[test]
+    fn loses_on_first_click_without_first_click_safety() {
+        let mut board = BoardBuilder::new(3, 3, 1)
+            .set_seed(12)
+            .set_first_click_policy(FirstClickPolicy::None)
+            .build()
+            .unwrap();
+
+        board.uncover_cell(1, 1);
+        assert_eq!(*board.get_state(), State::Finished(FinishedState::Lost));
+    }
+
+    #[test]
+    fn keeps_only_first_cell_safe() {
+        let mut board = BoardBuilder::new(3, 3, 8)
+            .set_seed(1)
+            .set_first_click_policy(FirstClickPolicy::SafeCell)
+            .build()
+            .unwrap();
+
+        board.uncover_cell(1, 1);
+        assert!(board.get_cell(1, 1) == Cell::Clue(8));
+        assert_eq!(*board.get_state(), State::Finished(FinishedState::Won));
+    }
+
+    #[test]
+    fn relocates_first_click_mine_to_top_left() {
+        let mut board = BoardBuilder::new(3, 3, 1)
+            .set_seed(12)
+            .set_first_click_policy(FirstClickPolicy::Anywhere)
+            .build()
+            .unwrap();
+
+        board.uncover_cell(1, 1);
+        assert!(board.get_cell(0, 0) == Cell::Mine);
+        assert!(board.get_cell(1, 1) == Cell::Clue(1));
+        assert_eq!(*board.get_state(), State::Playing);
+    }
+
+    #[test]
+    fn reproduces_layout_for_seed_and_policy() {
+        for policy in [
+            FirstClickPolicy::None,
+            FirstClickPolicy::SafeCell,
+            FirstClickPolicy::SafeOpening,
+            FirstClickPolicy::Anywhere,
+        ] {
+            let builder = BoardBuilder::new(16, 16, 40)
+                .set_seed(7)
+                .set_first_click_policy(policy);
+            let mut first_board = builder.build().unwrap();
+            let mut second_board = builder.build().unwrap();
+            first_board.uncover_cell(3, 5);
+            second_board.uncover_cell(3, 5);
+
+            for y in 0..16 {
+                for x in 0..16 {
+                    assert!(first_board.get_cell(x, y) == second_board.get_cell(x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn validates_mines_against_first_click_policy() {
+        let builder = BoardBuilder::new(3, 3, 8);
+        assert!(builder
+            .clone()
+            .set_first_click_policy(FirstClickPolicy::SafeCell)
+            .build()
+            .is_ok());
+        assert!(builder
+            .clone()
+            .set_first_click_policy(FirstClickPolicy::SafeOpening)
+            .build()
+            .is_err());
+        assert!(BoardBuilder::new(3, 3, 9)
+            .set_first_click_policy(FirstClickPolicy::None)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_first_click_without_room_for_policy() {
+        let mut board = BoardBuilder::new(3, 3, 8)
+            .set_first_click_policy(FirstClickPolicy::SafeCell)
+            .build()
+            .unwrap();
+        board.first_click_policy = FirstClickPolicy::SafeOpening;
+
+        assert_eq!(board.try_uncover(0, 0), Err(MinesweeperError::TooManyMines));
+        assert_eq!(*board.get_state(), State::New);
+        assert_eq!(board.get_initial_mines_number(), 8);
+        assert_eq!(board.get_mines_number(), 8);
+    }
+
+    #[test]
+    fn generates_board_solvable_without_guessing() {
+        for seed in 0..3 {
+            let mut board = BoardBuilder::new(16, 16, 40)
+                .set_seed(seed)
+                .set_no_guess(true)
+                .build()
+                .unwrap();
+            board.try_uncover(8, 8).unwrap();
+
+            while *board.get_state() == State::Playing {
+                let deductions =
+                    solver::deduce(board.get_visible_cells(), board.get_initial_mines_number());
+                assert!(!deductions.safe.is_empty());
+                for position in deductions.safe {
+                    board.uncover_cell(position.x, position.y);
+                }
+            }
+            assert_eq!(*board.get_state(), State::Finished(FinishedState::Won));
+        }
+    }
+
+    #[test]
+    fn reproduces_no_guess_layout_for_seed() {
+        let builder = BoardBuilder::new(16, 16, 40).set_seed(5).set_no_guess(true);
+        let mut first_board = builder.build().unwrap();
+        let mut second_board = builder.build().unwrap();
+        first_board.uncover_cell(0, 0);
+        second_board.uncover_cell(0, 0);
+
+        for y in 0..16 {
+            for x in 0..16 {
+                assert!(first_board.get_cell(x, y) == second_board.get_cell(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn gives_up_no_guess_generation_after_attempts() {
+        let mut board = BoardBuilder::new(2, 2, 1)
+            .set_seed(1)
+            .set_first_click_policy(FirstClickPolicy::SafeCell)
+            .set_no_guess(true)
+            .set_no_guess_attempts(20)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            board.try_uncover(0, 0),
+            Err(MinesweeperError::GenerationFailed)
+        );
+        assert_eq!(*board.get_state(), State::New);
+    }
+
+    #[test]
+    fn reports_invalid_moves() {
+        let mut board = get_test_builder().set_seed(12).build().unwrap();
+        assert_eq!(board.try_flag(0, 0), Err(MinesweeperError::NotStarted));
+        assert_eq!(
+            board.try_uncover(4, 0),
+            Err(MinesweeperError::OutOfBounds { x: 4, y: 0 })
+        );
+        assert_eq!(*board.get_state(), State::New);
+
+        board.try_uncover(0, 0).unwrap();
+        assert_eq!(
+            board.try_uncover(0, 0),
+            Err(MinesweeperError::AlreadyRevealed)
+        );
+        assert_eq!(board.try_flag(0, 0), Err(MinesweeperError::AlreadyRevealed));
+        board.try_flag(3, 1).unwrap();
+        assert_eq!(board.try_uncover(3, 1), Err(MinesweeperError::CellFlagged));
+        assert_eq!(
+            board.try_flag(0, 3),
+            Err(MinesweeperError::OutOfBounds { x: 0, y: 3 })
+        );
+
+        board.try_uncover(3, 2).unwrap();
+        assert_eq!(board.try_uncover(3, 0), Err(MinesweeperError::GameOver));
+        assert_eq!(board.try_flag(3, 0), Err(MinesweeperError::GameOver));
+    }
+
+    #[test]
+    fn plays_seeded_expert_board() {
+        let builder = BoardBuilder::from_difficulty(Difficulty::Expert).set_seed(99);
+        let mut board = builder.build().unwrap();
+        assert_eq!((board.get_height(), board.get_width()), (16, 30));
+
+        board.uncover_cell(15, 8);
+        assert_eq!(*board.get_state(), State::Playing);
+        assert!(board.get_cell(15, 8) == Cell::Empty);
+        let mines = (0..16)
+            .flat_map(|y| (0..30).map(move |x| (x, y)))
+            .filter(|(x, y)| board.get_cell(*x, *y) == Cell::Mine)
+            .count();
+        assert_eq!(mines, 99);
+
+        let mut replayed = builder.build().unwrap();
+        replayed.uncover_cell(15, 8);
+        assert!(replayed.get_visible_cells() == board.get_visible_cells());
+    }
+
+    #[test]
+    fn hints_only_at_safe_cells_and_mines() {
+        for seed in 0..5 {
+            let mut board = BoardBuilder::new(9, 9, 10).set_seed(seed).build().unwrap();
+            board.uncover_cell(4, 4);
+
+            let hint = board.hint();
+            for position in hint.safe.iter() {
+                assert!(board.get_cell(position.x, position.y) != Cell::Mine);
+            }
+            for position in hint.mines.iter() {
+                assert!(board.get_cell(position.x, position.y) == Cell::Mine);
+            }
+            assert!(hint.get_suggested_position().is_some());
+        }
+    }
 }
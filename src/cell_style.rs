@@ -0,0 +1,68 @@
+use crate::{
+    cell::{Cell, VisibleCell},
+    constants,
+    settings::Theme,
+};
+
+pub type Rgb = (u8, u8, u8);
+
+// Symbols that differ between frontends, as terminals cannot always draw
+// emoji in a single cell.
+pub struct CellSymbols {
+    pub mine: char,
+    pub flag: char,
+}
+
+pub const EMOJI_SYMBOLS: CellSymbols = CellSymbols {
+    mine: '💥',
+    flag: '🚩',
+};
+pub const ASCII_SYMBOLS: CellSymbols = CellSymbols {
+    mine: '*',
+    flag: 'F',
+};
+
+pub fn get_cell_text(visible_cell: &VisibleCell, symbols: &CellSymbols) -> char {
+    match visible_cell {
+        VisibleCell::Uncovered(cell) => match cell {
+            Cell::Mine => symbols.mine,
+            Cell::Clue(number) => char::from_digit(*number as u32, 10).unwrap(),
+            Cell::Empty => ' ',
+        },
+        VisibleCell::Covered => ' ',
+        VisibleCell::Flagged => symbols.flag,
+        VisibleCell::Questioned => '?',
+    }
+}
+
+pub fn get_cell_text_color(visible_cell: &VisibleCell) -> Rgb {
+    match visible_cell {
+        VisibleCell::Uncovered(cell) => match cell {
+            Cell::Mine => constants::RED,
+            Cell::Clue(number) => match number {
+                1 => constants::BLUE,
+                2 => constants::GREEN,
+                3 => constants::RED,
+                4 => constants::DARK_BLUE,
+                5 => constants::DARK_RED,
+                6 => constants::PERSIAN_GREEN,
+                7 => constants::BLACK,
+                8 => constants::GREY,
+                _ => constants::BLACK,
+            },
+            Cell::Empty => constants::BLACK,
+        },
+        VisibleCell::Covered => constants::BLACK,
+        VisibleCell::Flagged => constants::RED,
+        VisibleCell::Questioned => constants::BLACK,
+    }
+}
+
+pub fn get_cell_fill(theme: Theme, is_uncovered: bool) -> Rgb {
+    match (theme, is_uncovered) {
+        (Theme::Light, false) => constants::WHITE,
+        (Theme::Light, true) => constants::LIGHT_UNCOVERED_CELL,
+        (Theme::Dark, false) => constants::DARK_CELL,
+        (Theme::Dark, true) => constants::DARK_UNCOVERED_CELL,
+    }
+}
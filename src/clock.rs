@@ -0,0 +1,81 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+pub trait TimeSource {
+    fn now(&self) -> Duration;
+}
+
+pub struct SystemTimeSource {
+    origin: Instant,
+}
+
+impl Default for SystemTimeSource {
+    fn default() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+#[derive(Default)]
+pub struct ManualTimeSource {
+    now: Cell<Duration>,
+}
+
+impl ManualTimeSource {
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+}
+
+impl TimeSource for ManualTimeSource {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Clock {
+    offset: Duration,
+    started_at: Option<Duration>,
+}
+
+impl Clock {
+    pub fn resume_from(elapsed: Duration, now: Duration, running: bool) -> Self {
+        Self {
+            offset: elapsed,
+            started_at: if running { Some(now) } else { None },
+        }
+    }
+
+    pub fn start(&mut self, now: Duration) {
+        self.started_at.get_or_insert(now);
+    }
+
+    pub fn stop(&mut self, now: Duration) {
+        if let Some(started_at) = self.started_at.take() {
+            self.offset += now.saturating_sub(started_at);
+        }
+    }
+
+    pub fn elapsed(&self, now: Duration) -> Duration {
+        match self.started_at {
+            Some(started_at) => self.offset + now.saturating_sub(started_at),
+            None => self.offset,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some()
+    }
+}
@@ -1,28 +1,38 @@
-use crate::position::Position;
-use eframe::egui::Color32;
+use crate::cell_style::Rgb;
+use crate::position::Offset;
 
-pub const ADJACENT_TILE_OFFSETS: [Position; 8] = [
-    Position { x: -1, y: -1 },
-    Position { x: 0, y: -1 },
-    Position { x: 1, y: -1 },
-    Position { x: 1, y: 0 },
-    Position { x: 1, y: 1 },
-    Position { x: 0, y: 1 },
-    Position { x: -1, y: 1 },
-    Position { x: -1, y: 0 },
+pub const ADJACENT_TILE_OFFSETS: [Offset; 8] = [
+    Offset { x: -1, y: -1 },
+    Offset { x: 0, y: -1 },
+    Offset { x: 1, y: -1 },
+    Offset { x: 1, y: 0 },
+    Offset { x: 1, y: 1 },
+    Offset { x: 0, y: 1 },
+    Offset { x: -1, y: 1 },
+    Offset { x: -1, y: 0 },
 ];
 
-pub const DEFAULT_BOARD_HEIGHT: u8 = 9;
-pub const DEFAULT_BOARD_WIDTH: u8 = 9;
-pub const DEFAULT_BOARD_MINES: u8 = 10;
+pub const DEFAULT_BOARD_HEIGHT: usize = 9;
+pub const DEFAULT_BOARD_WIDTH: usize = 9;
+pub const DEFAULT_BOARD_MINES: u32 = 10;
+pub const DEFAULT_NO_GUESS_ATTEMPTS: u32 = 1000;
+pub const DEFAULT_HISTORY_DEPTH: usize = 100;
+pub const HIGH_SCORES_SHOWN: usize = 10;
 pub const CELL_SIZE: f32 = 30.0;
-pub const BLUE: Color32 = Color32::from_rgb(0, 0, 253);
-pub const GREEN: Color32 = Color32::from_rgb(1, 126, 0);
-pub const RED: Color32 = Color32::from_rgb(254, 0, 0);
-pub const DARK_BLUE: Color32 = Color32::from_rgb(1, 1, 128);
-pub const DARK_RED: Color32 = Color32::from_rgb(126, 3, 3);
-pub const PERSIAN_GREEN: Color32 = Color32::from_rgb(0, 128, 128);
-pub const GREY: Color32 = Color32::from_rgb(128, 128, 128);
-pub const BLACK: Color32 = Color32::BLACK;
+pub const MIN_CELL_SIZE: f32 = 16.0;
+pub const MAX_CELL_SIZE: f32 = 64.0;
+pub const BLUE: Rgb = (0, 0, 253);
+pub const GREEN: Rgb = (1, 126, 0);
+pub const RED: Rgb = (254, 0, 0);
+pub const DARK_BLUE: Rgb = (1, 1, 128);
+pub const DARK_RED: Rgb = (126, 3, 3);
+pub const PERSIAN_GREEN: Rgb = (0, 128, 128);
+pub const GREY: Rgb = (128, 128, 128);
+pub const BLACK: Rgb = (0, 0, 0);
+pub const WHITE: Rgb = (255, 255, 255);
+pub const DARK_CELL: Rgb = (90, 90, 90);
+pub const LIGHT_UNCOVERED_CELL: Rgb = (198, 198, 198);
+pub const DARK_UNCOVERED_CELL: Rgb = (55, 55, 55);
+pub const HINT_YELLOW: Rgb = (255, 236, 139);
 pub const WINDOW_X_OFFSET: f32 = 20.;
 pub const WINDOW_Y_OFFSET: f32 = 60.;
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct MoveCounters {
+    pub left_clicks: u32,
+    pub right_clicks: u32,
+    pub chords: u32,
+    pub effective_clicks: u32,
+}
+
+impl MoveCounters {
+    pub fn get_total_clicks(&self) -> u32 {
+        self.left_clicks + self.right_clicks + self.chords
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    #[default]
+    Beginner,
+    Intermediate,
+    Expert,
+    Custom {
+        height: usize,
+        width: usize,
+        mines: u32,
+    },
+}
+
+impl Difficulty {
+    pub const PRESETS: [Difficulty; 3] = [
+        Difficulty::Beginner,
+        Difficulty::Intermediate,
+        Difficulty::Expert,
+    ];
+
+    pub fn from_dimensions(height: usize, width: usize, mines: u32) -> Self {
+        Self::PRESETS
+            .iter()
+            .copied()
+            .find(|preset| preset.get_dimensions() == (height, width, mines))
+            .unwrap_or(Difficulty::Custom {
+                height,
+                width,
+                mines,
+            })
+    }
+
+    pub fn get_dimensions(&self) -> (usize, usize, u32) {
+        match *self {
+            Difficulty::Beginner => (9, 9, 10),
+            Difficulty::Intermediate => (16, 16, 40),
+            Difficulty::Expert => (16, 30, 99),
+            Difficulty::Custom {
+                height,
+                width,
+                mines,
+            } => (height, width, mines),
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Difficulty::Beginner => "Beginner",
+            Difficulty::Intermediate => "Intermediate",
+            Difficulty::Expert => "Expert",
+            Difficulty::Custom { .. } => "Custom",
+        }
+    }
+
+    pub fn is_preset(&self) -> bool {
+        !matches!(self, Difficulty::Custom { .. })
+    }
+}
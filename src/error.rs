@@ -0,0 +1,46 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MinesweeperError {
+    InvalidDimensions,
+    TooManyMines,
+    OutOfBounds { x: usize, y: usize },
+    NotStarted,
+    GameOver,
+    AlreadyRevealed,
+    CellFlagged,
+    GenerationFailed,
+    InvalidInput(&'static str),
+}
+
+impl fmt::Display for MinesweeperError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinesweeperError::InvalidDimensions => {
+                write!(f, "Board needs at least one row and one column")
+            }
+            MinesweeperError::TooManyMines => {
+                write!(
+                    f,
+                    "Wrong amount of mines in comparison with width and height"
+                )
+            }
+            MinesweeperError::OutOfBounds { x, y } => {
+                write!(f, "Cell ({}, {}) is outside of the board", x, y)
+            }
+            MinesweeperError::NotStarted => write!(f, "Game has not started yet"),
+            MinesweeperError::GameOver => write!(f, "Game is already over"),
+            MinesweeperError::AlreadyRevealed => write!(f, "Cell is already uncovered"),
+            MinesweeperError::CellFlagged => write!(f, "Cell is flagged"),
+            MinesweeperError::GenerationFailed => write!(
+                f,
+                "Could not generate a board solvable without guessing, try fewer mines"
+            ),
+            MinesweeperError::InvalidInput(field) => {
+                write!(f, "{} must be a non-negative whole number", field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MinesweeperError {}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FirstClickPolicy {
+    None,
+    SafeCell,
+    #[default]
+    SafeOpening,
+    Anywhere,
+}
+
+impl FirstClickPolicy {
+    pub fn get_safe_zone_size(&self, height: usize, width: usize) -> usize {
+        match self {
+            FirstClickPolicy::SafeOpening => height.min(3) * width.min(3),
+            _ => 1,
+        }
+    }
+}
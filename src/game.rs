@@ -0,0 +1,413 @@
+use crate::{
+    board::Board,
+    difficulty::Difficulty,
+    error::MinesweeperError,
+    high_scores::{BoardConfig, HighScores, ScoreEntry},
+    memento::{BoardDelta, Caretaker, Originator},
+    metrics,
+    move_outcome::MoveOutcome,
+    persistence::PersistenceError,
+    position::Position,
+    replay::{ActionKind, Replay, ReplayRecorder},
+    settings::Settings,
+    state::{FinishedState, State},
+};
+use std::path::{Path, PathBuf};
+
+// Everything a frontend needs to run a game: the board, its undo history,
+// the replay being recorded, the settings it was built from and the best times.
+pub struct Game {
+    pub settings: Settings,
+    board: Board,
+    caretaker: Caretaker<BoardDelta>,
+    recorder: ReplayRecorder,
+    hint: Option<Position>,
+    undo_used: bool,
+    loaded_from_file: bool,
+    high_scores: HighScores,
+    score_recorded: bool,
+    // Games without paths keep their settings and best times in memory only.
+    settings_path: Option<PathBuf>,
+    high_scores_path: Option<PathBuf>,
+}
+
+impl Game {
+    pub fn new(settings: Settings) -> Self {
+        let board = settings
+            .get_board_builder()
+            .build()
+            .unwrap_or_else(|_| Settings::default().get_board_builder().build().unwrap());
+
+        Self {
+            settings,
+            board,
+            caretaker: Caretaker::new(),
+            recorder: ReplayRecorder::default(),
+            hint: None,
+            undo_used: false,
+            loaded_from_file: false,
+            high_scores: HighScores::default(),
+            score_recorded: false,
+            settings_path: None,
+            high_scores_path: None,
+        }
+    }
+
+    // Reads the settings and best times from their default files, which later
+    // changes are written back to.
+    pub fn load_or_default() -> Self {
+        let mut game = Self::new(Settings::load_or_default());
+        game.settings_path = Settings::get_default_path();
+        game.high_scores_path = HighScores::get_default_path();
+        game.high_scores = game
+            .high_scores_path
+            .as_deref()
+            .and_then(|path| HighScores::load(path).ok())
+            .unwrap_or_default();
+
+        game
+    }
+
+    pub fn play(
+        &mut self,
+        kind: ActionKind,
+        x: usize,
+        y: usize,
+    ) -> Result<MoveOutcome, MinesweeperError> {
+        let memento = self.board.save_memento();
+        let outcome = self.recorder.play(&mut self.board, kind, x, y)?;
+        // Moves that change nothing would leave empty steps in the history.
+        if !outcome.is_empty() {
+            self.save_state(memento);
+        }
+
+        Ok(outcome)
+    }
+
+    pub fn restart(&mut self) {
+        let memento = self.board.save_memento();
+        self.board.reset();
+        self.save_state(memento);
+        self.undo_used = false;
+        self.loaded_from_file = false;
+        self.score_recorded = false;
+    }
+
+    // Builds a board for the difficulty with the other settings unchanged.
+    pub fn start_new_board(&mut self, difficulty: Difficulty) -> Result<(), MinesweeperError> {
+        let mut settings = self.settings.clone();
+        settings.difficulty = difficulty;
+        self.board = settings.get_board_builder().build()?;
+        self.settings = settings;
+        self.hint = None;
+        self.caretaker.clear();
+        self.undo_used = false;
+        self.loaded_from_file = false;
+        self.score_recorded = false;
+        // Settings that cannot be stored only last for this session.
+        self.save_settings().ok();
+
+        Ok(())
+    }
+
+    pub fn save_settings(&self) -> Result<(), PersistenceError> {
+        match &self.settings_path {
+            Some(path) => self.settings.save(path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn load_board(&mut self, board: Board) {
+        // The history only holds changes to the current board.
+        self.board = board;
+        self.hint = None;
+        self.caretaker.clear();
+        self.undo_used = false;
+        self.loaded_from_file = false;
+        self.score_recorded = false;
+    }
+
+    pub fn load_from(&mut self, path: &Path) -> Result<(), PersistenceError> {
+        self.load_board(Board::load_from(path)?);
+        // Saves are plain JSON that can be edited, so their games are unranked.
+        self.loaded_from_file = true;
+
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> bool {
+        if !self.can_undo() || !self.caretaker.undo(&mut self.board) {
+            return false;
+        }
+        self.hint = None;
+        // Times of games with undone moves are kept off the best times.
+        self.undo_used = true;
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        if !self.can_redo() || !self.caretaker.redo(&mut self.board) {
+            return false;
+        }
+        self.hint = None;
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.settings.ranked && self.caretaker.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.settings.ranked && self.caretaker.can_redo()
+    }
+
+    pub fn show_hint(&mut self) {
+        if *self.board.get_state() == State::Playing {
+            self.hint = self.board.hint().get_suggested_position();
+        }
+    }
+
+    pub fn get_hint(&self) -> Option<Position> {
+        self.hint
+    }
+
+    pub fn get_board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn get_replay(&self) -> Option<Replay> {
+        self.recorder.get_replay(&self.board)
+    }
+
+    // Only won games played here without undo make it to the best times.
+    pub fn get_score_entry(&self, player: &str) -> Option<(BoardConfig, ScoreEntry)> {
+        if *self.board.get_state() != State::Finished(FinishedState::Won)
+            || self.undo_used
+            || self.loaded_from_file
+        {
+            return None;
+        }
+        let bbbv_per_second = metrics::calculate(&self.board)
+            .map(|metrics| metrics.bbbv_per_second)
+            .unwrap_or_default();
+        let entry = ScoreEntry::new(
+            player,
+            self.board.get_elapsed_time().as_millis() as u64,
+            bbbv_per_second,
+            self.board.seed,
+        );
+
+        Some((BoardConfig::from_board(&self.board), entry))
+    }
+
+    // Adds a won game to the best times once and stores them. Returns the rank
+    // of the new entry, or None when the game does not count.
+    pub fn record_score(&mut self, player: &str) -> Result<Option<usize>, PersistenceError> {
+        if self.score_recorded {
+            return Ok(None);
+        }
+        let (config, entry) = match self.get_score_entry(player) {
+            Some(score) => score,
+            None => return Ok(None),
+        };
+        self.score_recorded = true;
+        let rank = self.high_scores.add(config, entry);
+        if let Some(path) = &self.high_scores_path {
+            self.high_scores.save(path)?;
+        }
+
+        Ok(Some(rank))
+    }
+
+    pub fn get_high_scores(&self) -> &HighScores {
+        &self.high_scores
+    }
+
+    fn save_state(&mut self, memento: Box<BoardDelta>) {
+        self.hint = None;
+        if !self.settings.ranked {
+            self.caretaker.add_memento(memento);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board_builder::BoardBuilder,
+        cell::VisibleCell,
+        difficulty::Difficulty,
+        game::Game,
+        high_scores::{BoardConfig, HighScores},
+        position::Position,
+        replay::ActionKind,
+        settings::Settings,
+        state::{FinishedState, State},
+    };
+    use std::{env, fs};
+
+    fn get_game(ranked: bool) -> Game {
+        let mut game = Game::new(Settings {
+            ranked,
+            ..Settings::default()
+        });
+        game.load_board(BoardBuilder::new(3, 4, 2).set_seed(12).build().unwrap());
+        game
+    }
+
+    #[test]
+    fn undoes_moves_outside_ranked_games() {
+        let mut game = get_game(false);
+        game.play(ActionKind::Uncover, 0, 0).unwrap();
+        game.play(ActionKind::Uncover, 3, 1).unwrap();
+        assert!(game.undo());
+        assert_eq!(*game.get_board().get_state(), State::Playing);
+
+        game.play(ActionKind::Uncover, 3, 0).unwrap();
+        assert!(game.get_score_entry("a").is_none());
+        assert_eq!(game.get_replay().unwrap().actions.len(), 2);
+
+        game.restart();
+        assert!(game.can_undo());
+        game.load_board(BoardBuilder::new(3, 4, 2).set_seed(12).build().unwrap());
+        game.play(ActionKind::Uncover, 0, 0).unwrap();
+        game.play(ActionKind::Uncover, 3, 0).unwrap();
+        assert!(game.get_score_entry("a").is_some());
+    }
+
+    #[test]
+    fn keeps_redo_after_moves_that_change_nothing() {
+        let mut game = get_game(false);
+        game.play(ActionKind::Uncover, 0, 0).unwrap();
+        game.play(ActionKind::Uncover, 3, 1).unwrap();
+        assert!(game.undo());
+
+        assert!(game.play(ActionKind::Uncover, 10, 10).is_err());
+        assert!(game.play(ActionKind::Flag, 0, 0).is_err());
+        assert!(game.play(ActionKind::Chord, 3, 0).unwrap().is_empty());
+        assert!(game.can_redo());
+        assert!(game.redo());
+        assert!(matches!(
+            game.get_board().get_visible_cell(3, 1),
+            VisibleCell::Uncovered(_)
+        ));
+        assert!(game.undo());
+        assert!(game.undo());
+        assert!(!game.can_undo());
+    }
+
+    #[test]
+    fn chords_uncovered_clue() {
+        /*
+           SEED 1
+           ____
+           1211
+           *2*1
+        */
+        let mut game = get_game(false);
+        game.load_board(BoardBuilder::new(3, 4, 2).set_seed(1).build().unwrap());
+        game.play(ActionKind::Uncover, 0, 0).unwrap();
+        game.play(ActionKind::Flag, 0, 2).unwrap();
+
+        let outcome = game.play(ActionKind::Chord, 0, 1).unwrap();
+        assert_eq!(outcome.revealed, vec![Position { x: 1, y: 2 }]);
+        assert!(matches!(
+            game.get_board().get_visible_cell(1, 2),
+            VisibleCell::Uncovered(_)
+        ));
+        assert!(game.undo());
+        assert_eq!(
+            game.get_board().get_visible_cell(1, 2),
+            VisibleCell::Covered
+        );
+    }
+
+    #[test]
+    fn records_replay_after_redo_past_move_that_changes_nothing() {
+        let mut game = get_game(false);
+        game.play(ActionKind::Uncover, 0, 0).unwrap();
+        game.play(ActionKind::Flag, 3, 1).unwrap();
+        assert!(game.undo());
+        assert!(game.play(ActionKind::Chord, 3, 0).unwrap().is_empty());
+        assert!(game.play(ActionKind::Flag, 0, 0).is_err());
+        assert!(game.redo());
+        assert_eq!(game.get_replay().unwrap().actions.len(), 2);
+
+        game.play(ActionKind::Uncover, 3, 0).unwrap();
+        assert_eq!(
+            *game.get_board().get_state(),
+            State::Finished(FinishedState::Won)
+        );
+        assert_eq!(game.get_replay().unwrap().actions.len(), 3);
+    }
+
+    #[test]
+    fn disables_undo_in_ranked_games() {
+        let mut game = get_game(true);
+        game.play(ActionKind::Uncover, 0, 0).unwrap();
+        game.play(ActionKind::Uncover, 3, 1).unwrap();
+        assert!(!game.can_undo());
+        assert!(!game.undo());
+
+        game.settings.ranked = false;
+        assert!(!game.undo());
+    }
+
+    #[test]
+    fn keeps_games_loaded_from_file_off_best_times() {
+        let path = env::temp_dir().join(format!(
+            "minesweeper_game_loaded_{}.json",
+            std::process::id()
+        ));
+        let mut game = get_game(false);
+        game.play(ActionKind::Uncover, 0, 0).unwrap();
+        game.get_board().save_to(&path).unwrap();
+
+        game.load_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        game.play(ActionKind::Uncover, 3, 0).unwrap();
+        assert_eq!(
+            *game.get_board().get_state(),
+            State::Finished(FinishedState::Won)
+        );
+        assert!(game.get_score_entry("a").is_none());
+    }
+
+    #[test]
+    fn records_won_game_once_and_saves_best_times() {
+        let path = env::temp_dir().join(format!(
+            "minesweeper_game_scores_{}.json",
+            std::process::id()
+        ));
+        let mut game = get_game(false);
+        game.high_scores_path = Some(path.clone());
+        game.play(ActionKind::Uncover, 0, 0).unwrap();
+        assert_eq!(game.record_score("a").unwrap(), None);
+
+        game.play(ActionKind::Uncover, 3, 0).unwrap();
+        assert_eq!(game.record_score("a").unwrap(), Some(1));
+        assert_eq!(game.record_score("a").unwrap(), None);
+        let config = BoardConfig::from_board(game.get_board());
+        let saved = HighScores::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(saved.get_top(config, 10).len(), 1);
+        assert_eq!(game.get_high_scores().get_top(config, 10).len(), 1);
+    }
+
+    #[test]
+    fn starts_new_board_with_current_settings() {
+        let mut game = get_game(false);
+        game.settings.question_marks = false;
+        game.play(ActionKind::Uncover, 0, 0).unwrap();
+
+        game.start_new_board(Difficulty::Intermediate).unwrap();
+        assert_eq!(game.settings.difficulty, Difficulty::Intermediate);
+        assert_eq!(game.get_board().get_height(), 16);
+        assert!(!game.get_board().question_marks);
+        assert!(!game.can_undo());
+        assert!(game
+            .start_new_board(Difficulty::from_dimensions(3, 3, 9))
+            .is_err());
+        assert_eq!(game.settings.difficulty, Difficulty::Intermediate);
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+use crate::cell::{Cell, VisibleCell};
+use crate::constants;
+use crate::position::Position;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    height: usize,
+    width: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(height: usize, width: usize, value: T) -> Self {
+        Self {
+            height,
+            width,
+            cells: vec![value; height * width],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &T {
+        &self.cells[self.index(x, y)]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        let index = self.index(x, y);
+        self.cells[index] = value;
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        assert!(
+            self.contains(x, y),
+            "position ({}, {}) is outside the grid",
+            x,
+            y
+        );
+        y * self.width + x
+    }
+
+    pub fn position(&self, index: usize) -> Position {
+        Position {
+            x: index % self.width,
+            y: index / self.width,
+        }
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get_adjacent_positions(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = Position> + '_ {
+        let position = Position { x, y };
+        constants::ADJACENT_TILE_OFFSETS
+            .iter()
+            .filter_map(move |offset| position.offset(offset))
+            .filter(move |adjacent| self.contains(adjacent.x, adjacent.y))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+}
+
+// Builds visible cells from rows such as "_1#": '#' is covered, '_' is an
+// uncovered empty cell and digits are uncovered clues.
+#[cfg(test)]
+impl Grid<VisibleCell> {
+    pub(crate) fn from_rows(rows: &[&str]) -> Self {
+        let mut grid = Grid::new(rows.len(), rows[0].len(), VisibleCell::Covered);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, character) in row.chars().enumerate() {
+                let visible_cell = match character {
+                    '#' => VisibleCell::Covered,
+                    '_' => VisibleCell::Uncovered(Cell::Empty),
+                    digit => VisibleCell::Uncovered(Cell::Clue(digit.to_digit(10).unwrap() as u8)),
+                };
+                grid.set(x, y, visible_cell);
+            }
+        }
+
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::Grid;
+
+    #[test]
+    #[should_panic]
+    fn panics_on_out_of_range_x() {
+        let grid = Grid::new(2, 4, 0);
+        grid.get(4, 0);
+    }
+}
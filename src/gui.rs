@@ -1,36 +1,81 @@
 use crate::{
     board::Board,
-    board_builder::BoardBuilder,
     cell::{Cell, VisibleCell},
+    cell_style::{self, Rgb},
     constants,
-    memento::{BoardMemento, Caretaker, Originator},
+    difficulty::Difficulty,
+    error::MinesweeperError,
+    first_click_policy::FirstClickPolicy,
+    game::Game,
+    high_scores::{BoardConfig, HighScores},
+    metrics,
+    position::Position,
+    replay::{ActionKind, Replay, ReplayPlayer},
+    settings::{Settings, Theme},
     state::{FinishedState, State},
 };
 use eframe::{
-    egui::{self, Color32, TextStyle, Ui, Vec2},
+    egui::{self, Color32, PointerButton, Sense, TextStyle, Ui, Vec2},
     epi,
 };
+use std::{path::Path, time::Duration};
 
 pub struct MinesweeperApp {
-    caretaker: Caretaker<BoardMemento>,
-    board: Board,
+    game: Game,
     error: Option<String>,
     settings_modal_opened: bool,
     height_input: String,
     width_input: String,
     mines_input: String,
+    file_modal: Option<FileAction>,
+    path_input: String,
+    file_error: Option<String>,
+    high_scores_opened: bool,
+    high_scores_error: Option<String>,
+    player_name_input: String,
+    replay_viewer: Option<ReplayViewer>,
+}
+
+struct ReplayViewer {
+    player: ReplayPlayer,
+    playing: bool,
+    speed: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FileAction {
+    Save,
+    Open,
+    SaveReplay,
+    OpenReplay,
 }
 
 impl MinesweeperApp {
-    pub fn calculate_size(height: u8, width: u8) -> Vec2 {
-        Vec2 {
-            x: constants::WINDOW_X_OFFSET + width as f32 * constants::CELL_SIZE,
-            y: constants::WINDOW_Y_OFFSET + height as f32 * constants::CELL_SIZE,
+    pub fn new(game: Game) -> Self {
+        let board = game.get_board();
+
+        Self {
+            error: None,
+            settings_modal_opened: false,
+            height_input: board.get_height().to_string(),
+            width_input: board.get_width().to_string(),
+            mines_input: board.get_initial_mines_number().to_string(),
+            game,
+            file_modal: None,
+            path_input: String::from("minesweeper.json"),
+            file_error: None,
+            high_scores_opened: false,
+            high_scores_error: None,
+            player_name_input: std::env::var("USER").unwrap_or_else(|_| String::from("Player")),
+            replay_viewer: None,
         }
     }
 
-    fn save_state(&mut self) {
-        self.caretaker.add_memento(self.board.save_memento());
+    pub fn calculate_size(height: usize, width: usize, cell_size: f32) -> Vec2 {
+        Vec2 {
+            x: constants::WINDOW_X_OFFSET + width as f32 * cell_size,
+            y: constants::WINDOW_Y_OFFSET + height as f32 * cell_size,
+        }
     }
 
     fn draw_top_menu(&mut self, ctx: &egui::CtxRef, frame: &mut epi::Frame<'_>) {
@@ -40,38 +85,94 @@ impl MinesweeperApp {
                     if ui.button("Settings").clicked() {
                         self.settings_modal_opened = true;
                     }
+                    if ui.button("Save").clicked() {
+                        self.file_modal = Some(FileAction::Save);
+                    }
+                    if ui.button("Open").clicked() {
+                        self.file_modal = Some(FileAction::Open);
+                    }
+                    if ui.button("Save replay").clicked() {
+                        self.file_modal = Some(FileAction::SaveReplay);
+                    }
+                    if ui.button("Open replay").clicked() {
+                        self.file_modal = Some(FileAction::OpenReplay);
+                    }
                     if ui.button("Restart").clicked() {
-                        self.save_state();
-                        self.board.reset();
+                        self.game.restart();
                     }
-                    if ui.button("Back").clicked() {
-                        let last_memento = self.caretaker.get_last_memento();
-                        if let Some(last_memento) = last_memento {
-                            self.board.restore_from_memento(last_memento);
-                        }
+                    if ui
+                        .add(egui::Button::new("Undo (Ctrl+Z)").enabled(self.game.can_undo()))
+                        .clicked()
+                    {
+                        self.game.undo();
+                    }
+                    if ui
+                        .add(egui::Button::new("Redo (Ctrl+Y)").enabled(self.game.can_redo()))
+                        .clicked()
+                    {
+                        self.game.redo();
+                    }
+                    if ui.button("Best times").clicked() {
+                        self.high_scores_opened = true;
+                    }
+                    if ui.button("Hint").clicked() {
+                        self.game.show_hint();
                     }
                     if ui.button("Quit").clicked() {
                         frame.quit();
                     }
                 });
+                egui::menu::menu(ui, "Game", |ui| {
+                    for preset in Difficulty::PRESETS {
+                        if ui
+                            .selectable_label(
+                                self.game.settings.difficulty == preset,
+                                preset.get_name(),
+                            )
+                            .clicked()
+                        {
+                            if let Err(err) = self.start_new_board(frame, preset) {
+                                self.error = Some(err.to_string());
+                                self.settings_modal_opened = true;
+                            }
+                        }
+                    }
+                    if ui
+                        .selectable_label(!self.game.settings.difficulty.is_preset(), "Custom...")
+                        .clicked()
+                    {
+                        self.settings_modal_opened = true;
+                    }
+                });
             });
         });
     }
 
+    fn get_board(&self) -> &Board {
+        match &self.replay_viewer {
+            Some(viewer) => viewer.player.get_board(),
+            None => self.game.get_board(),
+        }
+    }
+
     fn draw_board_panel(&mut self, ctx: &egui::CtxRef) {
         egui::CentralPanel::default().show(ctx, |ui| {
+            let board = self.get_board();
+            let (height, width) = (board.get_height(), board.get_width());
             ui.add(egui::Label::new(format!(
-                "Mines: {}",
-                self.board.get_mines_number()
+                "Mines: {}    Time: {}    Clicks: {}",
+                board.get_mines_number(),
+                board.get_elapsed_time().as_secs(),
+                board.get_counters().get_total_clicks()
             )));
 
             ui.vertical(|ui| {
                 ui.spacing_mut().item_spacing.y = 0.0;
-                for y in 0..self.board.get_height() {
+                for y in 0..height {
                     ui.horizontal_wrapped(|ui| {
                         ui.spacing_mut().item_spacing.x = 0.0;
 
-                        for x in 0..self.board.get_width() {
+                        for x in 0..width {
                             self.draw_cell(ui, x, y);
                         }
                     });
@@ -86,15 +187,43 @@ impl MinesweeperApp {
                 FinishedState::Won => ui.label("You have won!"),
                 FinishedState::Lost => ui.label("You have lost!"),
             };
+            if let Some(metrics) = metrics::calculate(self.game.get_board()) {
+                ui.label(format!("Time: {:.2} s", metrics.seconds));
+                ui.label(format!("3BV: {}/{}", metrics.solved_bbbv, metrics.bbbv));
+                ui.label(format!("3BV/s: {:.2}", metrics.bbbv_per_second));
+                ui.label(format!("IOE: {:.2}", metrics.ioe));
+                ui.label(format!("Throughput: {:.2}", metrics.throughput));
+                ui.label(format!("RQP: {:.2}", metrics.rqp));
+            }
 
             if ui.button("Start new game").clicked() {
-                self.save_state();
-                self.board.reset();
+                self.game.restart();
             }
         });
     }
 
+    fn start_new_board(
+        &mut self,
+        frame: &mut epi::Frame<'_>,
+        difficulty: Difficulty,
+    ) -> Result<(), MinesweeperError> {
+        self.game.start_new_board(difficulty)?;
+
+        let (height, width, mines) = difficulty.get_dimensions();
+        self.height_input = height.to_string();
+        self.width_input = width.to_string();
+        self.mines_input = mines.to_string();
+        frame.set_window_size(Self::calculate_size(
+            height,
+            width,
+            self.game.settings.cell_size,
+        ));
+
+        Ok(())
+    }
+
     fn draw_settings_modal(&mut self, ctx: &egui::CtxRef, frame: &mut epi::Frame<'_>) {
+        let previous_settings = self.game.settings.clone();
         egui::Window::new("Settings").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.label("Height: ");
@@ -108,6 +237,44 @@ impl MinesweeperApp {
                 ui.label("Mines: ");
                 ui.text_edit_singleline(&mut self.mines_input);
             });
+            ui.checkbox(&mut self.game.settings.question_marks, "Question marks");
+            ui.checkbox(&mut self.game.settings.no_guess, "No guessing");
+            ui.checkbox(&mut self.game.settings.ranked, "Ranked (no undo)");
+            ui.horizontal(|ui| {
+                ui.label("First click: ");
+                ui.radio_value(
+                    &mut self.game.settings.first_click_policy,
+                    FirstClickPolicy::None,
+                    "Risky",
+                );
+                ui.radio_value(
+                    &mut self.game.settings.first_click_policy,
+                    FirstClickPolicy::SafeCell,
+                    "Safe cell",
+                );
+                ui.radio_value(
+                    &mut self.game.settings.first_click_policy,
+                    FirstClickPolicy::SafeOpening,
+                    "Safe opening",
+                );
+                ui.radio_value(
+                    &mut self.game.settings.first_click_policy,
+                    FirstClickPolicy::Anywhere,
+                    "Move mine",
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label("Theme: ");
+                ui.radio_value(&mut self.game.settings.theme, Theme::Light, "Light");
+                ui.radio_value(&mut self.game.settings.theme, Theme::Dark, "Dark");
+            });
+            ui.add(
+                egui::Slider::new(
+                    &mut self.game.settings.cell_size,
+                    constants::MIN_CELL_SIZE..=constants::MAX_CELL_SIZE,
+                )
+                .text("Cell size"),
+            );
             if let Some(error) = &self.error {
                 ui.label(format!("Error: {}", error));
             }
@@ -115,30 +282,29 @@ impl MinesweeperApp {
                 let height = match self.height_input.parse() {
                     Ok(value) => value,
                     Err(_) => {
+                        self.error = Some(MinesweeperError::InvalidInput("Height").to_string());
                         return;
                     }
                 };
                 let width = match self.width_input.parse() {
                     Ok(value) => value,
                     Err(_) => {
+                        self.error = Some(MinesweeperError::InvalidInput("Width").to_string());
                         return;
                     }
                 };
                 let mines = match self.mines_input.parse() {
                     Ok(value) => value,
                     Err(_) => {
+                        self.error = Some(MinesweeperError::InvalidInput("Mines").to_string());
                         return;
                     }
                 };
-                let new_board = BoardBuilder::new(height, width, mines).build();
-                match new_board {
-                    Ok(board) => self.board = board,
-                    Err(err) => {
-                        self.error = Some(err.to_string());
-                        return;
-                    }
-                };
-                frame.set_window_size(Self::calculate_size(height, width));
+                let difficulty = Difficulty::from_dimensions(height, width, mines);
+                if let Err(err) = self.start_new_board(frame, difficulty) {
+                    self.error = Some(err.to_string());
+                    return;
+                }
                 self.settings_modal_opened = false;
                 self.error = None;
             }
@@ -146,84 +312,274 @@ impl MinesweeperApp {
                 self.settings_modal_opened = false;
             }
         });
+        // Options apply right away, so they are stored as soon as they change.
+        if self.game.settings != previous_settings {
+            self.game.save_settings().ok();
+        }
+    }
+
+    fn draw_file_modal(
+        &mut self,
+        ctx: &egui::CtxRef,
+        frame: &mut epi::Frame<'_>,
+        file_action: FileAction,
+    ) {
+        let title = match file_action {
+            FileAction::Save => "Save game",
+            FileAction::Open => "Open game",
+            FileAction::SaveReplay => "Save replay",
+            FileAction::OpenReplay => "Open replay",
+        };
+        egui::Window::new(title).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("File: ");
+                ui.text_edit_singleline(&mut self.path_input);
+            });
+            if let Some(error) = &self.file_error {
+                ui.label(format!("Error: {}", error));
+            }
+            if ui.button(title).clicked() {
+                let path = Path::new(&self.path_input);
+                let result = match file_action {
+                    FileAction::Save => self
+                        .game
+                        .get_board()
+                        .save_to(path)
+                        .map_err(|err| err.to_string()),
+                    FileAction::Open => self
+                        .game
+                        .load_from(path)
+                        .map(|()| {
+                            let board = self.game.get_board();
+                            frame.set_window_size(Self::calculate_size(
+                                board.get_height(),
+                                board.get_width(),
+                                self.game.settings.cell_size,
+                            ));
+                        })
+                        .map_err(|err| err.to_string()),
+                    FileAction::SaveReplay => match self.game.get_replay() {
+                        Some(replay) => replay.save(path).map_err(|err| err.to_string()),
+                        None => Err(String::from("Only games started here can be replayed")),
+                    },
+                    FileAction::OpenReplay => Replay::load(path)
+                        .map_err(|err| err.to_string())
+                        .and_then(|replay| ReplayPlayer::new(replay).map_err(|err| err.to_string()))
+                        .map(|player| self.open_replay_viewer(frame, player)),
+                };
+                match result {
+                    Ok(()) => {
+                        self.file_modal = None;
+                        self.file_error = None;
+                    }
+                    Err(err) => self.file_error = Some(err.to_string()),
+                }
+            }
+            if ui.button("Quit").clicked() {
+                self.file_modal = None;
+                self.file_error = None;
+            }
+        });
     }
 
-    fn draw_cell(&mut self, ui: &mut Ui, x: u8, y: u8) {
-        let cell = self.board.get_visible_cell(x, y);
-
-        let cell_button = ui.add_sized(
-            [constants::CELL_SIZE, constants::CELL_SIZE],
-            egui::Button::new(Self::get_cell_text(&cell))
-                .text_color(Self::get_cell_text_color(&cell))
-                .text_style(TextStyle::Heading)
-                .fill(Color32::WHITE)
-                .enabled(match &cell {
-                    VisibleCell::Uncovered(_) => false,
-                    _ => true,
-                }),
-        );
-
-        if cell_button.clicked() {
-            self.save_state();
-            self.board.uncover_cell(x, y);
-        } else if cell_button.secondary_clicked() {
-            self.save_state();
-            self.board.flag_cell(x, y);
-        }
-    }
-
-    fn get_cell_text(visible_cell: &VisibleCell) -> char {
-        match visible_cell {
-            VisibleCell::Uncovered(cell) => match cell {
-                Cell::Mine => '💥',
-                Cell::Clue(number) => char::from_digit(*number as u32, 10).unwrap(),
-                Cell::Empty => ' ',
-            },
-            VisibleCell::Covered => ' ',
-            VisibleCell::Flagged => '🚩',
-        }
-    }
-
-    fn get_cell_text_color(visible_cell: &VisibleCell) -> Color32 {
-        match visible_cell {
-            VisibleCell::Uncovered(cell) => match cell {
-                Cell::Mine => constants::RED,
-                Cell::Clue(number) => match number {
-                    1 => constants::BLUE,
-                    2 => constants::GREEN,
-                    3 => constants::RED,
-                    4 => constants::DARK_BLUE,
-                    5 => constants::DARK_RED,
-                    6 => constants::PERSIAN_GREEN,
-                    7 => constants::BLACK,
-                    8 => constants::GREY,
-                    _ => constants::BLACK,
-                },
-                Cell::Empty => constants::BLACK,
-            },
-            VisibleCell::Covered => constants::BLACK,
-            VisibleCell::Flagged => constants::RED,
+    fn open_replay_viewer(&mut self, frame: &mut epi::Frame<'_>, player: ReplayPlayer) {
+        let config = player.get_replay().config;
+        frame.set_window_size(Self::calculate_size(
+            config.height,
+            config.width,
+            self.game.settings.cell_size,
+        ));
+        self.replay_viewer = Some(ReplayViewer {
+            player,
+            playing: false,
+            speed: 1.0,
+        });
+    }
+
+    fn draw_replay_window(&mut self, ctx: &egui::CtxRef, frame: &mut epi::Frame<'_>) {
+        let mut closed = false;
+        if let Some(viewer) = &mut self.replay_viewer {
+            if viewer.playing {
+                let elapsed = ctx.input().unstable_dt * viewer.speed;
+                viewer.player.advance(Duration::from_secs_f32(elapsed));
+                viewer.playing = !viewer.player.is_finished();
+                ctx.request_repaint();
+            }
+
+            egui::Window::new("Replay").show(ctx, |ui| {
+                ui.label(format!(
+                    "Time: {:.1} / {:.1} s",
+                    viewer.player.get_time().as_secs_f32(),
+                    viewer.player.get_replay().get_duration().as_secs_f32()
+                ));
+                ui.horizontal(|ui| {
+                    let play_text = if viewer.playing { "Pause" } else { "Play" };
+                    if ui.button(play_text).clicked() {
+                        if viewer.player.is_finished() {
+                            viewer.player.restart();
+                        }
+                        viewer.playing = !viewer.playing;
+                    }
+                    if ui.button("Step").clicked() {
+                        viewer.playing = false;
+                        viewer.player.step();
+                    }
+                    if ui.button("Restart").clicked() {
+                        viewer.player.restart();
+                    }
+                });
+                ui.add(egui::Slider::new(&mut viewer.speed, 0.25..=8.0).text("Speed"));
+                if ui.button("Close").clicked() {
+                    closed = true;
+                }
+            });
+        }
+
+        if closed {
+            self.replay_viewer = None;
+            let board = self.game.get_board();
+            frame.set_window_size(Self::calculate_size(
+                board.get_height(),
+                board.get_width(),
+                self.game.settings.cell_size,
+            ));
+        }
+    }
+
+    fn record_score(&mut self) {
+        match self.game.record_score(&self.player_name_input) {
+            Ok(Some(_)) => self.high_scores_error = None,
+            Ok(None) => {}
+            Err(err) => self.high_scores_error = Some(err.to_string()),
+        }
+    }
+
+    fn draw_high_scores_window(&mut self, ctx: &egui::CtxRef) {
+        egui::Window::new("Best times").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Player: ");
+                ui.text_edit_singleline(&mut self.player_name_input);
+            });
+            if let Some(error) = &self.high_scores_error {
+                ui.label(format!("Error: {}", error));
+            }
+
+            let current = BoardConfig::from_board(self.game.get_board());
+            ui.heading("Current board");
+            Self::draw_score_table(ui, self.game.get_high_scores(), current);
+
+            for (title, configs) in [
+                (
+                    "Standard",
+                    self.game
+                        .get_high_scores()
+                        .get_standard_configs()
+                        .collect::<Vec<_>>(),
+                ),
+                (
+                    "Custom",
+                    self.game
+                        .get_high_scores()
+                        .get_custom_configs()
+                        .collect::<Vec<_>>(),
+                ),
+            ] {
+                ui.heading(title);
+                for config in configs {
+                    egui::CollapsingHeader::new(format!(
+                        "{}x{}, {} mines",
+                        config.height, config.width, config.mines
+                    ))
+                    .show(ui, |ui| {
+                        Self::draw_score_table(ui, self.game.get_high_scores(), config);
+                    });
+                }
+            }
+
+            if ui.button("Close").clicked() {
+                self.high_scores_opened = false;
+            }
+        });
+    }
+
+    fn draw_score_table(ui: &mut Ui, high_scores: &HighScores, config: BoardConfig) {
+        let entries = high_scores.get_top(config, constants::HIGH_SCORES_SHOWN);
+        if entries.is_empty() {
+            ui.label("No wins yet");
+        }
+        for (rank, entry) in entries.iter().enumerate() {
+            let seed = entry
+                .seed
+                .map(|seed| seed.to_string())
+                .unwrap_or_else(|| String::from("-"));
+            ui.label(format!(
+                "{}. {}  {:.2} s  3BV/s {:.2}  {}  seed {}",
+                rank + 1,
+                entry.player,
+                entry.time_millis as f64 / 1000.0,
+                entry.bbbv_per_second,
+                entry.get_date_text(),
+                seed
+            ));
+        }
+    }
+
+    fn draw_cell(&mut self, ui: &mut Ui, x: usize, y: usize) {
+        let cell = self.get_board().get_visible_cell(x, y);
+        let is_uncovered = matches!(&cell, VisibleCell::Uncovered(_));
+
+        // Uncovered cells stay enabled, as disabled widgets never report the
+        // clicks used for chording.
+        let cell_button = ui
+            .add_sized(
+                [self.game.settings.cell_size, self.game.settings.cell_size],
+                egui::Button::new(cell_style::get_cell_text(&cell, &cell_style::EMOJI_SYMBOLS))
+                    .text_color(to_color32(cell_style::get_cell_text_color(&cell)))
+                    .text_style(TextStyle::Heading)
+                    .fill(to_color32(
+                        if self.game.get_hint() == Some(Position { x, y }) {
+                            constants::HINT_YELLOW
+                        } else {
+                            cell_style::get_cell_fill(self.game.settings.theme, is_uncovered)
+                        },
+                    )),
+            )
+            .interact(Sense::click());
+        if self.replay_viewer.is_some() {
+            return;
+        }
+
+        let pointer = &ui.input().pointer;
+        let chorded = matches!(&cell, VisibleCell::Uncovered(Cell::Clue(_)))
+            && (cell_button.middle_clicked()
+                || (cell_button.clicked() && pointer.button_down(PointerButton::Secondary))
+                || (cell_button.secondary_clicked()
+                    && pointer.button_down(PointerButton::Primary)));
+
+        if chorded {
+            self.game.play(ActionKind::Chord, x, y).ok();
+        } else if !is_uncovered {
+            if cell_button.clicked() {
+                if let Err(err @ MinesweeperError::GenerationFailed) =
+                    self.game.play(ActionKind::Uncover, x, y)
+                {
+                    self.error = Some(err.to_string());
+                    self.settings_modal_opened = true;
+                }
+            } else if cell_button.secondary_clicked() {
+                self.game.play(ActionKind::Flag, x, y).ok();
+            }
         }
     }
 }
 
+fn to_color32((r, g, b): Rgb) -> Color32 {
+    Color32::from_rgb(r, g, b)
+}
+
 impl Default for MinesweeperApp {
     fn default() -> Self {
-        Self {
-            caretaker: Caretaker::new(),
-            board: BoardBuilder::new(
-                constants::DEFAULT_BOARD_HEIGHT,
-                constants::DEFAULT_BOARD_WIDTH,
-                constants::DEFAULT_BOARD_MINES,
-            )
-            .build()
-            .unwrap(),
-            error: None,
-            settings_modal_opened: false,
-            height_input: format!("{}", constants::DEFAULT_BOARD_HEIGHT),
-            width_input: format!("{}", constants::DEFAULT_BOARD_WIDTH),
-            mines_input: format!("{}", constants::DEFAULT_BOARD_MINES),
-        }
+        Self::new(Game::new(Settings::default()))
     }
 }
 
@@ -233,14 +589,46 @@ impl epi::App for MinesweeperApp {
     }
 
     fn update(&mut self, ctx: &egui::CtxRef, frame: &mut epi::Frame<'_>) {
+        ctx.set_visuals(match self.game.settings.theme {
+            Theme::Light => egui::Visuals::light(),
+            Theme::Dark => egui::Visuals::dark(),
+        });
+        let (undo_pressed, redo_pressed) = {
+            let input = ctx.input();
+            (
+                input.modifiers.command && input.key_pressed(egui::Key::Z),
+                input.modifiers.command && input.key_pressed(egui::Key::Y),
+            )
+        };
+        if self.replay_viewer.is_none() {
+            if undo_pressed {
+                self.game.undo();
+            } else if redo_pressed {
+                self.game.redo();
+            }
+        }
         self.draw_top_menu(ctx, frame);
         self.draw_board_panel(ctx);
-        match self.board.get_state().clone() {
-            State::Finished(finished_state) => self.draw_end_of_game_modal(ctx, finished_state),
-            _ => {}
-        };
+        let state = self.game.get_board().get_state().clone();
+        if state == State::Playing {
+            ctx.request_repaint();
+        }
+        if state == State::Finished(FinishedState::Won) {
+            self.record_score();
+        }
+        if self.replay_viewer.is_some() {
+            self.draw_replay_window(ctx, frame);
+        } else if let State::Finished(finished_state) = state {
+            self.draw_end_of_game_modal(ctx, finished_state);
+        }
         if self.settings_modal_opened {
             self.draw_settings_modal(ctx, frame);
         }
+        if let Some(file_action) = self.file_modal {
+            self.draw_file_modal(ctx, frame, file_action);
+        }
+        if self.high_scores_opened {
+            self.draw_high_scores_window(ctx);
+        }
     }
 }
@@ -0,0 +1,267 @@
+use crate::{board::Board, difficulty::Difficulty, persistence::PersistenceError};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const HIGH_SCORES_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BoardConfig {
+    pub height: usize,
+    pub width: usize,
+    pub mines: u32,
+}
+
+impl BoardConfig {
+    pub fn from_board(board: &Board) -> Self {
+        Self {
+            height: board.get_height(),
+            width: board.get_width(),
+            mines: board.get_initial_mines_number(),
+        }
+    }
+
+    pub fn is_standard(&self) -> bool {
+        Difficulty::from_dimensions(self.height, self.width, self.mines).is_preset()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    pub player: String,
+    pub time_millis: u64,
+    pub bbbv_per_second: f64,
+    pub date: u64,
+    pub seed: Option<u64>,
+}
+
+impl ScoreEntry {
+    pub fn new(player: &str, time_millis: u64, bbbv_per_second: f64, seed: Option<u64>) -> Self {
+        let date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        Self {
+            player: player.to_string(),
+            time_millis,
+            bbbv_per_second,
+            date,
+            seed,
+        }
+    }
+
+    pub fn get_date_text(&self) -> String {
+        // Days since the epoch to a proleptic Gregorian date, after Howard Hinnant.
+        let days = (self.date / 86_400) as i64 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScoreTable {
+    config: BoardConfig,
+    entries: Vec<ScoreEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScores {
+    version: u32,
+    tables: Vec<ScoreTable>,
+}
+
+impl Default for HighScores {
+    fn default() -> Self {
+        Self {
+            version: HIGH_SCORES_VERSION,
+            tables: vec![],
+        }
+    }
+}
+
+impl HighScores {
+    pub fn get_default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|directory| directory.join("minesweeper").join("high_scores.json"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, PersistenceError> {
+        let high_scores: HighScores = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if high_scores.version != HIGH_SCORES_VERSION {
+            return Err(PersistenceError::UnsupportedVersion(high_scores.version));
+        }
+
+        Ok(high_scores)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), PersistenceError> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    // Returns the 1-based rank of the new entry in its table.
+    pub fn add(&mut self, config: BoardConfig, entry: ScoreEntry) -> usize {
+        let table_index = match self.tables.iter().position(|table| table.config == config) {
+            Some(index) => index,
+            None => {
+                self.tables.push(ScoreTable {
+                    config,
+                    entries: vec![],
+                });
+                self.tables.sort_by_key(|table| table.config);
+                self.tables
+                    .iter()
+                    .position(|table| table.config == config)
+                    .unwrap()
+            }
+        };
+
+        let entries = &mut self.tables[table_index].entries;
+        let rank = entries
+            .iter()
+            .position(|other| is_better(&entry, other))
+            .unwrap_or(entries.len());
+        entries.insert(rank, entry);
+
+        rank + 1
+    }
+
+    pub fn get_top(&self, config: BoardConfig, count: usize) -> &[ScoreEntry] {
+        match self.tables.iter().find(|table| table.config == config) {
+            Some(table) => &table.entries[..count.min(table.entries.len())],
+            None => &[],
+        }
+    }
+
+    pub fn get_standard_configs(&self) -> impl Iterator<Item = BoardConfig> + '_ {
+        self.get_configs().filter(|config| config.is_standard())
+    }
+
+    pub fn get_custom_configs(&self) -> impl Iterator<Item = BoardConfig> + '_ {
+        self.get_configs().filter(|config| !config.is_standard())
+    }
+
+    fn get_configs(&self) -> impl Iterator<Item = BoardConfig> + '_ {
+        self.tables.iter().map(|table| table.config)
+    }
+}
+
+fn is_better(entry: &ScoreEntry, other: &ScoreEntry) -> bool {
+    entry.time_millis < other.time_millis
+        || (entry.time_millis == other.time_millis && entry.bbbv_per_second > other.bbbv_per_second)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::high_scores::{BoardConfig, HighScores, ScoreEntry};
+    use std::{env, fs};
+
+    const BEGINNER: BoardConfig = BoardConfig {
+        height: 9,
+        width: 9,
+        mines: 10,
+    };
+
+    fn get_entry(player: &str, time_millis: u64) -> ScoreEntry {
+        ScoreEntry {
+            player: player.to_string(),
+            time_millis,
+            bbbv_per_second: 1.0,
+            date: 0,
+            seed: Some(1),
+        }
+    }
+
+    #[test]
+    fn ranks_entries_by_time() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(high_scores.add(BEGINNER, get_entry("a", 9000)), 1);
+        assert_eq!(high_scores.add(BEGINNER, get_entry("b", 5000)), 1);
+        assert_eq!(high_scores.add(BEGINNER, get_entry("c", 7000)), 2);
+
+        let top = high_scores.get_top(BEGINNER, 2);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].player, "b");
+        assert_eq!(top[1].player, "c");
+        assert!(high_scores
+            .get_top(
+                BoardConfig {
+                    height: 9,
+                    width: 9,
+                    mines: 11
+                },
+                10
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn tracks_custom_sizes_separately() {
+        let custom = BoardConfig {
+            height: 9,
+            width: 9,
+            mines: 12,
+        };
+        let mut high_scores = HighScores::default();
+        high_scores.add(custom, get_entry("a", 3000));
+        high_scores.add(BEGINNER, get_entry("b", 4000));
+
+        assert_eq!(
+            high_scores.get_standard_configs().collect::<Vec<_>>(),
+            vec![BEGINNER]
+        );
+        assert_eq!(
+            high_scores.get_custom_configs().collect::<Vec<_>>(),
+            vec![custom]
+        );
+        assert_eq!(high_scores.get_top(BEGINNER, 10)[0].player, "b");
+    }
+
+    #[test]
+    fn saves_and_loads_high_scores() {
+        let path = env::temp_dir()
+            .join(format!("minesweeper_scores_{}", std::process::id()))
+            .join("high_scores.json");
+        let mut high_scores = HighScores::default();
+        high_scores.add(BEGINNER, get_entry("a", 3000));
+
+        high_scores.save(&path).unwrap();
+        let loaded = HighScores::load(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(
+            loaded.get_top(BEGINNER, 10),
+            high_scores.get_top(BEGINNER, 10)
+        );
+    }
+
+    #[test]
+    fn formats_date() {
+        let mut entry = get_entry("a", 1000);
+        assert_eq!(entry.get_date_text(), "1970-01-01");
+        entry.date = 951_782_400;
+        assert_eq!(entry.get_date_text(), "2000-02-29");
+        entry.date = 1_792_281_600;
+        assert_eq!(entry.get_date_text(), "2026-10-18");
+    }
+}
@@ -1,8 +1,26 @@
 pub mod board;
 pub mod board_builder;
 pub mod cell;
+pub mod cell_style;
+pub mod clock;
 pub mod constants;
+pub mod counters;
+pub mod difficulty;
+pub mod error;
+pub mod first_click_policy;
+pub mod game;
+pub mod grid;
+#[cfg(feature = "gui")]
 pub mod gui;
+pub mod high_scores;
 pub mod memento;
+pub mod metrics;
+pub mod move_outcome;
+pub mod observer;
+pub mod persistence;
 pub mod position;
+pub mod probability;
+pub mod replay;
+pub mod settings;
+pub mod solver;
 pub mod state;
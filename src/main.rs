@@ -1,11 +1,16 @@
-use minesweeper::{constants, gui};
+use minesweeper::{game::Game, gui};
 
 fn main() {
-    let app = gui::MinesweeperApp::default();
-    let mut options = eframe::NativeOptions::default();
-    options.initial_window_size = Some(gui::MinesweeperApp::calculate_size(
-        constants::DEFAULT_BOARD_HEIGHT,
-        constants::DEFAULT_BOARD_WIDTH,
-    ));
+    let game = Game::load_or_default();
+    let (height, width, _) = game.settings.difficulty.get_dimensions();
+    let options = eframe::NativeOptions {
+        initial_window_size: Some(gui::MinesweeperApp::calculate_size(
+            height,
+            width,
+            game.settings.cell_size,
+        )),
+        ..Default::default()
+    };
+    let app = gui::MinesweeperApp::new(game);
     eframe::run_native(Box::new(app), options);
 }
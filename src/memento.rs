@@ -1,21 +1,85 @@
 use crate::{
     cell::{Cell, VisibleCell},
+    clock::Clock,
+    constants,
+    counters::MoveCounters,
+    first_click_policy::FirstClickPolicy,
+    grid::Grid,
     state::State,
 };
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    rc::{Rc, Weak},
+};
 
+// Undo history with a cursor: mementos before the cursor can be undone and
+// those after it redone. Undoing swaps the current state into the history,
+// so the same slot holds the state to return to on redo.
 pub struct Caretaker<G: Memento> {
-    mementos: Vec<Box<G>>,
+    mementos: VecDeque<Box<G>>,
+    cursor: usize,
+    max_depth: usize,
 }
 
 impl<G: Memento> Caretaker<G> {
     pub fn new() -> Self {
-        Self { mementos: vec![] }
+        Self::with_max_depth(constants::DEFAULT_HISTORY_DEPTH)
+    }
+    pub fn with_max_depth(max_depth: usize) -> Self {
+        Self {
+            mementos: VecDeque::new(),
+            cursor: 0,
+            max_depth,
+        }
     }
     pub fn add_memento(&mut self, memento: Box<G>) {
-        self.mementos.push(memento);
+        if self.max_depth == 0 {
+            return;
+        }
+        self.mementos.truncate(self.cursor);
+        self.mementos.push_back(memento);
+        if self.mementos.len() > self.max_depth {
+            self.mementos.pop_front();
+        }
+        self.cursor = self.mementos.len();
+    }
+    pub fn undo<O: Originator<G>>(&mut self, originator: &mut O) -> bool {
+        if !self.can_undo() {
+            return false;
+        }
+        self.cursor -= 1;
+        self.swap(originator);
+        true
+    }
+    pub fn redo<O: Originator<G>>(&mut self, originator: &mut O) -> bool {
+        if !self.can_redo() {
+            return false;
+        }
+        self.swap(originator);
+        self.cursor += 1;
+        true
+    }
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+    pub fn can_redo(&self) -> bool {
+        self.cursor < self.mementos.len()
+    }
+    pub fn clear(&mut self) {
+        self.mementos.clear();
+        self.cursor = 0;
+    }
+    fn swap<O: Originator<G>>(&mut self, originator: &mut O) {
+        let current = originator.save_memento();
+        let memento = std::mem::replace(&mut self.mementos[self.cursor], current);
+        originator.restore_from_memento(memento);
     }
-    pub fn get_last_memento(&mut self) -> Option<Box<G>> {
-        self.mementos.pop()
+}
+
+impl<G: Memento> Default for Caretaker<G> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -27,13 +91,97 @@ pub trait Originator<G: Memento> {
 pub trait Memento {}
 
 pub struct BoardMemento {
+    pub seed: Option<u64>,
     pub state: State,
-    pub height: u8,
-    pub width: u8,
-    pub initial_mines: u8,
-    pub mines: u8,
-    pub visible_cells: Vec<Vec<VisibleCell>>,
-    pub cells: Vec<Vec<Cell>>,
+    pub question_marks: bool,
+    pub first_click_policy: FirstClickPolicy,
+    pub no_guess: bool,
+    pub no_guess_attempts: u32,
+    pub height: usize,
+    pub width: usize,
+    pub initial_mines: u32,
+    pub mines: u32,
+    pub uncovered_cells: usize,
+    pub visible_cells: Grid<VisibleCell>,
+    pub cells: Grid<Cell>,
+    pub clock: Clock,
+    pub counters: MoveCounters,
 }
 
 impl Memento for BoardMemento {}
+
+// Keeps the cells that change after the memento was taken instead of whole
+// grids. The board writes the previous value of every cell it changes into
+// the changes of its latest delta, so restoring puts those values back.
+pub struct BoardDelta {
+    pub seed: Option<u64>,
+    pub state: State,
+    pub question_marks: bool,
+    pub first_click_policy: FirstClickPolicy,
+    pub no_guess: bool,
+    pub no_guess_attempts: u32,
+    pub initial_mines: u32,
+    pub mines: u32,
+    pub uncovered_cells: usize,
+    pub clock: Clock,
+    pub counters: MoveCounters,
+    pub(crate) changes: Rc<RefCell<Vec<CellChange>>>,
+}
+
+impl BoardDelta {
+    pub fn get_change_count(&self) -> usize {
+        self.changes.borrow().len()
+    }
+}
+
+impl Memento for BoardDelta {}
+
+pub(crate) enum CellChange {
+    Hidden(usize, Cell),
+    Visible(usize, VisibleCell),
+}
+
+// Changes go to the delta taken last, and are dropped once that delta is gone.
+#[derive(Default)]
+pub(crate) struct Journal {
+    changes: RefCell<Weak<RefCell<Vec<CellChange>>>>,
+}
+
+// Copies of a board, such as the ones used to check solvability, must not
+// write into the history of the original.
+impl Clone for Journal {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Journal {
+    pub(crate) fn start(&self) -> Rc<RefCell<Vec<CellChange>>> {
+        let changes = Rc::default();
+        *self.changes.borrow_mut() = Rc::downgrade(&changes);
+        changes
+    }
+
+    pub(crate) fn record(&self, change: CellChange) {
+        if let Some(changes) = self.changes.borrow().upgrade() {
+            changes.borrow_mut().push(change);
+        }
+    }
+
+    pub(crate) fn record_replaced<T: Clone + PartialEq>(
+        &self,
+        grid: &Grid<T>,
+        new_grid: &Grid<T>,
+        to_change: fn(usize, T) -> CellChange,
+    ) {
+        if let Some(changes) = self.changes.borrow().upgrade() {
+            changes.borrow_mut().extend(
+                grid.iter()
+                    .zip(new_grid.iter())
+                    .enumerate()
+                    .filter(|(_, (value, new_value))| value != new_value)
+                    .map(|(index, (value, _))| to_change(index, value.clone())),
+            );
+        }
+    }
+}
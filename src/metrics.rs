@@ -0,0 +1,218 @@
+use crate::{
+    board::Board,
+    cell::{Cell, VisibleCell},
+    grid::Grid,
+    state::State,
+};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameMetrics {
+    pub bbbv: u32,
+    pub solved_bbbv: u32,
+    pub seconds: f64,
+    pub bbbv_per_second: f64,
+    pub ioe: f64,
+    pub throughput: f64,
+    pub rqp: f64,
+}
+
+/// Bechtel's Board Benchmark Value: the least number of left clicks needed to
+/// clear the board, i.e. every opening plus every clue not bordering one.
+pub fn calculate_3bv(cells: &Grid<Cell>) -> u32 {
+    let visible_cells = Grid::new(cells.get_height(), cells.get_width(), VisibleCell::Covered);
+    count_3bv(cells, &visible_cells).0
+}
+
+/// 3BV of a started game together with the rates derived from the engine's
+/// clock and click counters. Rates use the solved part of the 3BV so lost games
+/// still get meaningful values.
+pub fn calculate(board: &Board) -> Option<GameMetrics> {
+    if *board.get_state() == State::New {
+        return None;
+    }
+
+    let (bbbv, solved_bbbv) = count_3bv(board.get_cells(), board.get_visible_cells());
+    let seconds = board.get_elapsed_time().as_secs_f64();
+    let counters = board.get_counters();
+    let solved = solved_bbbv as f64;
+    let bbbv_per_second = divide(solved, seconds);
+
+    Some(GameMetrics {
+        bbbv,
+        solved_bbbv,
+        seconds,
+        bbbv_per_second,
+        ioe: divide(solved, counters.get_total_clicks() as f64),
+        throughput: divide(solved, counters.effective_clicks as f64),
+        rqp: divide(seconds, bbbv_per_second),
+    })
+}
+
+fn divide(dividend: f64, divisor: f64) -> f64 {
+    if divisor > 0.0 {
+        dividend / divisor
+    } else {
+        0.0
+    }
+}
+
+// Returns the 3BV and how much of it is already uncovered.
+fn count_3bv(cells: &Grid<Cell>, visible_cells: &Grid<VisibleCell>) -> (u32, u32) {
+    let is_uncovered = |index: usize| {
+        let position = visible_cells.position(index);
+        matches!(
+            visible_cells.get(position.x, position.y),
+            VisibleCell::Uncovered(_)
+        )
+    };
+    let mut marked = vec![false; cells.len()];
+    let mut bbbv = 0;
+    let mut solved_bbbv = 0;
+
+    for (index, cell) in cells.iter().enumerate() {
+        if *cell != Cell::Empty || marked[index] {
+            continue;
+        }
+
+        bbbv += 1;
+        let mut solved = false;
+        let mut queue = VecDeque::from(vec![index]);
+        marked[index] = true;
+        while let Some(current) = queue.pop_front() {
+            let position = cells.position(current);
+            if *cells.get(position.x, position.y) != Cell::Empty {
+                continue;
+            }
+            // Border clues can be uncovered one by one, so only the opening's
+            // empty cells tell whether it was opened.
+            solved |= is_uncovered(current);
+            for adjacent in cells.get_adjacent_positions(position.x, position.y) {
+                let adjacent_index = cells.index(adjacent.x, adjacent.y);
+                if !marked[adjacent_index] {
+                    marked[adjacent_index] = true;
+                    queue.push_back(adjacent_index);
+                }
+            }
+        }
+        if solved {
+            solved_bbbv += 1;
+        }
+    }
+
+    for (index, cell) in cells.iter().enumerate() {
+        if let Cell::Clue(_) = cell {
+            if !marked[index] {
+                bbbv += 1;
+                if is_uncovered(index) {
+                    solved_bbbv += 1;
+                }
+            }
+        }
+    }
+
+    (bbbv, solved_bbbv)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board_builder::BoardBuilder,
+        cell::Cell,
+        clock::ManualTimeSource,
+        grid::Grid,
+        metrics::{calculate, calculate_3bv, count_3bv},
+    };
+    use std::{rc::Rc, time::Duration};
+
+    fn get_cells(rows: &[&str]) -> Grid<Cell> {
+        let mut cells = Grid::new(rows.len(), rows[0].len(), Cell::Empty);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, character) in row.chars().enumerate() {
+                if character == '*' {
+                    cells.set(x, y, Cell::Mine);
+                }
+            }
+        }
+        for index in 0..cells.len() {
+            let position = cells.position(index);
+            if *cells.get(position.x, position.y) == Cell::Mine {
+                continue;
+            }
+            let mines = cells
+                .get_adjacent_positions(position.x, position.y)
+                .filter(|adjacent| *cells.get(adjacent.x, adjacent.y) == Cell::Mine)
+                .count();
+            if mines > 0 {
+                cells.set(position.x, position.y, Cell::Clue(mines as u8));
+            }
+        }
+
+        cells
+    }
+
+    #[test]
+    fn counts_openings() {
+        /*
+           *1_
+           121
+           _1*
+        */
+        assert_eq!(calculate_3bv(&get_cells(&["*..", "...", "..*"])), 2);
+    }
+
+    #[test]
+    fn counts_clues_away_from_openings() {
+        assert_eq!(calculate_3bv(&get_cells(&["*.*"])), 1);
+        assert_eq!(calculate_3bv(&get_cells(&["*.*.", "....", "...."])), 3);
+    }
+
+    #[test]
+    fn leaves_opening_unsolved_after_border_clue() {
+        /*
+           *1_
+           121
+           _1*
+        */
+        let cells = get_cells(&["*..", "...", "..*"]);
+        assert_eq!(
+            count_3bv(&cells, &Grid::from_rows(&["#1#", "###", "###"])),
+            (2, 0)
+        );
+        assert_eq!(
+            count_3bv(&cells, &Grid::from_rows(&["#1_", "###", "###"])),
+            (2, 1)
+        );
+    }
+
+    #[test]
+    fn calculates_rates_for_won_game() {
+        /*
+           SEED 1
+           ____
+           1211
+           *2*1
+        */
+        let time_source = Rc::new(ManualTimeSource::default());
+        let mut board = BoardBuilder::new(3, 4, 2)
+            .set_seed(1)
+            .set_time_source(time_source.clone())
+            .build()
+            .unwrap();
+        assert!(calculate(&board).is_none());
+
+        board.uncover_cell(0, 0);
+        board.flag_cell(0, 2);
+        time_source.advance(Duration::from_secs(2));
+        board.uncover_cell(1, 2);
+        board.uncover_cell(3, 2);
+
+        let metrics = calculate(&board).unwrap();
+        assert_eq!(metrics.bbbv, 3);
+        assert_eq!(metrics.solved_bbbv, 3);
+        assert!((metrics.bbbv_per_second - 1.5).abs() < 1e-9);
+        assert!((metrics.ioe - 0.75).abs() < 1e-9);
+        assert!((metrics.throughput - 0.75).abs() < 1e-9);
+        assert!((metrics.rqp - 2.0 / 1.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{cell::VisibleCell, position::Position, state::State};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagChange {
+    pub position: Position,
+    pub from: VisibleCell,
+    pub to: VisibleCell,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveOutcome {
+    pub revealed: Vec<Position>,
+    pub flag_change: Option<FlagChange>,
+    pub mine_hit: Option<Position>,
+    pub previous_state: State,
+    pub state: State,
+}
+
+impl MoveOutcome {
+    pub fn new(state: State) -> Self {
+        Self {
+            revealed: vec![],
+            flag_change: None,
+            mine_hit: None,
+            previous_state: state.clone(),
+            state,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.revealed.is_empty() && self.flag_change.is_none()
+    }
+
+    pub fn is_state_changed(&self) -> bool {
+        self.previous_state != self.state
+    }
+}
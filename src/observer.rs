@@ -0,0 +1,9 @@
+use crate::{cell::Cell, move_outcome::FlagChange, position::Position};
+
+pub trait BoardObserver {
+    fn on_game_started(&mut self) {}
+    fn on_cell_revealed(&mut self, _position: Position, _cell: &Cell) {}
+    fn on_flag_changed(&mut self, _flag_change: &FlagChange) {}
+    fn on_game_won(&mut self) {}
+    fn on_game_lost(&mut self, _mine: Position) {}
+}
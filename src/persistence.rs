@@ -0,0 +1,485 @@
+use crate::{
+    board::Board,
+    cell::{Cell, VisibleCell},
+    clock::Clock,
+    counters::MoveCounters,
+    first_click_policy::FirstClickPolicy,
+    grid::Grid,
+    memento::{BoardMemento, Originator},
+    state::{FinishedState, State},
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs, io, path::Path, time::Duration};
+
+pub const SAVE_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug)]
+pub enum PersistenceError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    UnsupportedVersion(u32),
+    Inconsistent(&'static str),
+}
+
+impl fmt::Display for PersistenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistenceError::Io(err) => write!(f, "Could not access the save file: {}", err),
+            PersistenceError::Format(err) => write!(f, "Save file is not valid: {}", err),
+            PersistenceError::UnsupportedVersion(version) => {
+                write!(f, "Save file version {} is not supported", version)
+            }
+            PersistenceError::Inconsistent(reason) => {
+                write!(f, "Save file is inconsistent: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PersistenceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PersistenceError::Io(err) => Some(err),
+            PersistenceError::Format(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PersistenceError {
+    fn from(err: io::Error) -> Self {
+        PersistenceError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for PersistenceError {
+    fn from(err: serde_json::Error) -> Self {
+        PersistenceError::Format(err)
+    }
+}
+
+// Rows of `cells` use '*' for mines, digits for clues and '.' for empty cells.
+// Rows of `visible_cells` show what the player sees: the cell itself once
+// uncovered, '#' when covered, 'F' when flagged and '?' when questioned.
+// Version 1 files have no clock or counters and load with both at zero.
+#[derive(Serialize, Deserialize)]
+struct SavedGame {
+    version: u32,
+    height: usize,
+    width: usize,
+    seed: Option<u64>,
+    state: State,
+    initial_mines: u32,
+    mines: u32,
+    question_marks: bool,
+    first_click_policy: FirstClickPolicy,
+    no_guess: bool,
+    no_guess_attempts: u32,
+    cells: Vec<String>,
+    visible_cells: Vec<String>,
+    #[serde(default)]
+    elapsed_millis: u64,
+    #[serde(default)]
+    counters: MoveCounters,
+}
+
+pub fn save(board: &Board, path: &Path) -> Result<(), PersistenceError> {
+    let saved_game = SavedGame::from_board(board);
+    fs::write(path, serde_json::to_string_pretty(&saved_game)?)?;
+
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<Board, PersistenceError> {
+    let saved_game: SavedGame = serde_json::from_str(&fs::read_to_string(path)?)?;
+    if saved_game.version == 0 || saved_game.version > SAVE_FORMAT_VERSION {
+        return Err(PersistenceError::UnsupportedVersion(saved_game.version));
+    }
+
+    saved_game.into_board()
+}
+
+impl SavedGame {
+    fn from_board(board: &Board) -> Self {
+        let (height, width) = (board.get_height(), board.get_width());
+        let rows = |to_char: &dyn Fn(usize, usize) -> char| -> Vec<String> {
+            (0..height)
+                .map(|y| (0..width).map(|x| to_char(x, y)).collect())
+                .collect()
+        };
+
+        Self {
+            version: SAVE_FORMAT_VERSION,
+            height,
+            width,
+            seed: board.seed,
+            state: board.get_state().clone(),
+            initial_mines: board.get_initial_mines_number(),
+            mines: board.get_mines_number(),
+            question_marks: board.question_marks,
+            first_click_policy: board.first_click_policy,
+            no_guess: board.no_guess,
+            no_guess_attempts: board.no_guess_attempts,
+            cells: rows(&|x, y| cell_to_char(&board.get_cell(x, y))),
+            visible_cells: rows(&|x, y| visible_cell_to_char(&board.get_visible_cell(x, y))),
+            elapsed_millis: board.get_elapsed_time().as_millis() as u64,
+            counters: board.get_counters(),
+        }
+    }
+
+    fn into_board(self) -> Result<Board, PersistenceError> {
+        if self.height == 0 || self.width == 0 {
+            return Err(PersistenceError::Inconsistent(
+                "board needs at least one row and one column",
+            ));
+        }
+        if self.cells.len() != self.height || self.visible_cells.len() != self.height {
+            return Err(PersistenceError::Inconsistent(
+                "number of rows does not match the height",
+            ));
+        }
+
+        let mut cells = Grid::new(self.height, self.width, Cell::Empty);
+        let mut visible_cells = Grid::new(self.height, self.width, VisibleCell::Covered);
+        for (y, (row, visible_row)) in self.cells.iter().zip(self.visible_cells.iter()).enumerate()
+        {
+            if row.chars().count() != self.width || visible_row.chars().count() != self.width {
+                return Err(PersistenceError::Inconsistent(
+                    "number of columns does not match the width",
+                ));
+            }
+            for (x, (character, visible_character)) in
+                row.chars().zip(visible_row.chars()).enumerate()
+            {
+                let cell = char_to_cell(character)
+                    .ok_or(PersistenceError::Inconsistent("unknown cell character"))?;
+                let visible_cell = match visible_character {
+                    '#' => VisibleCell::Covered,
+                    'F' => VisibleCell::Flagged,
+                    '?' => VisibleCell::Questioned,
+                    other if other == character => VisibleCell::Uncovered(cell.clone()),
+                    _ => {
+                        return Err(PersistenceError::Inconsistent(
+                            "uncovered cell does not match the board",
+                        ))
+                    }
+                };
+                cells.set(x, y, cell);
+                visible_cells.set(x, y, visible_cell);
+            }
+        }
+
+        let uncovered_cells = validate(&self, &cells, &visible_cells)?;
+
+        let mut board = Board::new(self.height, self.width, self.initial_mines);
+        let clock = Clock::resume_from(
+            Duration::from_millis(self.elapsed_millis),
+            board.get_current_time(),
+            self.state == State::Playing,
+        );
+        board.restore_from_memento(Box::new(BoardMemento {
+            seed: self.seed,
+            state: self.state,
+            question_marks: self.question_marks,
+            first_click_policy: self.first_click_policy,
+            no_guess: self.no_guess,
+            no_guess_attempts: self.no_guess_attempts,
+            height: self.height,
+            width: self.width,
+            initial_mines: self.initial_mines,
+            mines: self.mines,
+            uncovered_cells,
+            visible_cells,
+            cells,
+            clock,
+            counters: self.counters,
+        }));
+
+        Ok(board)
+    }
+}
+
+fn validate(
+    saved_game: &SavedGame,
+    cells: &Grid<Cell>,
+    visible_cells: &Grid<VisibleCell>,
+) -> Result<usize, PersistenceError> {
+    let mines = cells.iter().filter(|cell| **cell == Cell::Mine).count();
+    let flags = visible_cells
+        .iter()
+        .filter(|visible_cell| **visible_cell == VisibleCell::Flagged)
+        .count();
+    let uncovered_mines = visible_cells
+        .iter()
+        .filter(|visible_cell| **visible_cell == VisibleCell::Uncovered(Cell::Mine))
+        .count();
+    let uncovered_cells = visible_cells
+        .iter()
+        .filter(|visible_cell| matches!(visible_cell, VisibleCell::Uncovered(_)))
+        .count()
+        - uncovered_mines;
+    let initial_mines = saved_game.initial_mines as usize;
+
+    if initial_mines > cells.len() {
+        return Err(PersistenceError::Inconsistent(
+            "more mines than cells on the board",
+        ));
+    }
+    if flags > initial_mines || saved_game.mines as usize != initial_mines - flags {
+        return Err(PersistenceError::Inconsistent(
+            "mine counter does not match the flags",
+        ));
+    }
+
+    if saved_game.state == State::New {
+        let safe_zone = saved_game
+            .first_click_policy
+            .get_safe_zone_size(saved_game.height, saved_game.width);
+        if initial_mines + safe_zone > cells.len() {
+            return Err(PersistenceError::Inconsistent(
+                "no room for the first click with this many mines",
+            ));
+        }
+        if mines > 0 || cells.iter().any(|cell| *cell != Cell::Empty) || uncovered_cells > 0 {
+            return Err(PersistenceError::Inconsistent(
+                "new game cannot have mines or uncovered cells",
+            ));
+        }
+        return Ok(0);
+    }
+
+    if mines != initial_mines {
+        return Err(PersistenceError::Inconsistent(
+            "number of mines does not match the board",
+        ));
+    }
+    for (index, cell) in cells.iter().enumerate() {
+        if *cell == Cell::Mine {
+            continue;
+        }
+        let position = cells.position(index);
+        let adjacent_mines = cells
+            .get_adjacent_positions(position.x, position.y)
+            .filter(|adjacent| *cells.get(adjacent.x, adjacent.y) == Cell::Mine)
+            .count();
+        let expected = match adjacent_mines {
+            0 => Cell::Empty,
+            clue => Cell::Clue(clue as u8),
+        };
+        if *cell != expected {
+            return Err(PersistenceError::Inconsistent(
+                "clues do not match the mines",
+            ));
+        }
+    }
+
+    let all_uncovered = uncovered_cells + initial_mines == cells.len();
+    let consistent_state = match saved_game.state {
+        State::Finished(FinishedState::Lost) => uncovered_mines > 0,
+        State::Finished(FinishedState::Won) => uncovered_mines == 0 && all_uncovered,
+        _ => uncovered_mines == 0 && !all_uncovered,
+    };
+    if !consistent_state {
+        return Err(PersistenceError::Inconsistent(
+            "game state does not match the uncovered cells",
+        ));
+    }
+
+    Ok(uncovered_cells)
+}
+
+fn cell_to_char(cell: &Cell) -> char {
+    match cell {
+        Cell::Mine => '*',
+        Cell::Clue(clue) => char::from_digit(*clue as u32, 10).unwrap(),
+        Cell::Empty => '.',
+    }
+}
+
+fn visible_cell_to_char(visible_cell: &VisibleCell) -> char {
+    match visible_cell {
+        VisibleCell::Uncovered(cell) => cell_to_char(cell),
+        VisibleCell::Covered => '#',
+        VisibleCell::Flagged => 'F',
+        VisibleCell::Questioned => '?',
+    }
+}
+
+fn char_to_cell(character: char) -> Option<Cell> {
+    match character {
+        '*' => Some(Cell::Mine),
+        '.' => Some(Cell::Empty),
+        digit => match digit.to_digit(10) {
+            Some(clue) if (1..=8).contains(&clue) => Some(Cell::Clue(clue as u8)),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::Board,
+        board_builder::BoardBuilder,
+        cell::{Cell, VisibleCell},
+        clock::ManualTimeSource,
+        persistence::PersistenceError,
+        state::{FinishedState, State},
+    };
+    use std::{env, fs, path::PathBuf, rc::Rc, time::Duration};
+
+    fn get_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("minesweeper_{}_{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn saves_and_loads_game_in_progress() {
+        let path = get_path("in_progress");
+        let time_source = Rc::new(ManualTimeSource::default());
+        let mut board = BoardBuilder::new(3, 4, 2)
+            .set_seed(12)
+            .set_time_source(time_source.clone())
+            .build()
+            .unwrap();
+        board.uncover_cell(0, 0);
+        board.flag_cell(3, 1);
+        time_source.advance(Duration::from_secs(42));
+
+        board.save_to(&path).unwrap();
+        let mut loaded = Board::load_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.seed, Some(12));
+        assert_eq!(loaded.get_mines_number(), 1);
+        assert!(loaded.get_elapsed_time() >= Duration::from_secs(42));
+        assert!(loaded.get_counters() == board.get_counters());
+        assert!(loaded.get_visible_cells() == board.get_visible_cells());
+        assert_eq!(*loaded.get_state(), State::Playing);
+        loaded.uncover_cell(3, 0);
+        assert_eq!(*loaded.get_state(), State::Finished(FinishedState::Won));
+    }
+
+    #[test]
+    fn saves_and_loads_game_after_opening_clears_flag() {
+        let path = get_path("cleared_flag");
+        let mut board = BoardBuilder::new(16, 30, 60).set_seed(0).build().unwrap();
+        board.uncover_cell(0, 0);
+
+        // Every neighbour of an empty cell is safe and gets cleared by its opening.
+        let cells = board.get_cells().clone();
+        let (empty, flagged) = (0..cells.len())
+            .map(|index| cells.position(index))
+            .filter(|position| {
+                board.get_cell(position.x, position.y) == Cell::Empty
+                    && board.get_visible_cell(position.x, position.y) == VisibleCell::Covered
+            })
+            .find_map(|position| {
+                cells
+                    .get_adjacent_positions(position.x, position.y)
+                    .find(|adjacent| {
+                        board.get_visible_cell(adjacent.x, adjacent.y) == VisibleCell::Covered
+                    })
+                    .map(|adjacent| (position, adjacent))
+            })
+            .unwrap();
+        board.flag_cell(flagged.x, flagged.y);
+        assert_eq!(board.get_mines_number(), 59);
+        board.uncover_cell(empty.x, empty.y);
+        assert!(matches!(
+            board.get_visible_cell(flagged.x, flagged.y),
+            VisibleCell::Uncovered(_)
+        ));
+        assert_eq!(board.get_mines_number(), 60);
+
+        board.save_to(&path).unwrap();
+        let loaded = Board::load_from(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap().get_mines_number(), 60);
+    }
+
+    #[test]
+    fn saves_and_loads_new_game() {
+        let path = get_path("new");
+        let board = BoardBuilder::new(3, 4, 2)
+            .set_no_guess(true)
+            .build()
+            .unwrap();
+
+        board.save_to(&path).unwrap();
+        let loaded = Board::load_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(*loaded.get_state(), State::New);
+        assert_eq!(loaded.get_initial_mines_number(), 2);
+        assert!(loaded.no_guess);
+    }
+
+    #[test]
+    fn rejects_clues_not_matching_mines() {
+        let path = get_path("wrong_clue");
+        let mut board = BoardBuilder::new(3, 4, 2).set_seed(12).build().unwrap();
+        board.uncover_cell(0, 0);
+        board.save_to(&path).unwrap();
+
+        let mut saved_game: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let row: String = saved_game["cells"][0]
+            .as_str()
+            .unwrap()
+            .chars()
+            .map(|character| match character {
+                '.' => '1',
+                '1' => '2',
+                other => other,
+            })
+            .collect();
+        saved_game["cells"][0] = serde_json::Value::String(row);
+        fs::write(&path, saved_game.to_string()).unwrap();
+        let result = Board::load_from(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(PersistenceError::Inconsistent(_))));
+    }
+
+    #[test]
+    fn loads_version_1_save_without_clock() {
+        let path = get_path("version_1");
+        let mut board = BoardBuilder::new(3, 4, 2).set_seed(12).build().unwrap();
+        board.uncover_cell(0, 0);
+        board.save_to(&path).unwrap();
+
+        let mut saved_game: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let fields = saved_game.as_object_mut().unwrap();
+        fields.insert(String::from("version"), serde_json::Value::from(1));
+        fields.remove("elapsed_millis");
+        fields.remove("counters");
+        fs::write(&path, saved_game.to_string()).unwrap();
+        let loaded = Board::load_from(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.get_counters().get_total_clicks(), 0);
+        assert!(loaded.get_visible_cells() == board.get_visible_cells());
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let path = get_path("version");
+        let board = BoardBuilder::new(3, 4, 2).build().unwrap();
+        board.save_to(&path).unwrap();
+
+        let contents =
+            fs::read_to_string(&path)
+                .unwrap()
+                .replacen("\"version\": 2", "\"version\": 99", 1);
+        fs::write(&path, contents).unwrap();
+        let result = Board::load_from(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(PersistenceError::UnsupportedVersion(99))
+        ));
+    }
+}
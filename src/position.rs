@@ -1,4 +1,19 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Position {
-    pub x: i8,
-    pub y: i8,
+    pub x: usize,
+    pub y: usize,
+}
+
+pub struct Offset {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Position {
+    pub fn offset(&self, offset: &Offset) -> Option<Position> {
+        Some(Position {
+            x: self.x.checked_add_signed(offset.x)?,
+            y: self.y.checked_add_signed(offset.y)?,
+        })
+    }
 }
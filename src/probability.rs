@@ -0,0 +1,529 @@
+use crate::cell::{Cell, VisibleCell};
+use crate::grid::Grid;
+
+struct Constraint {
+    cells: Vec<usize>,
+    mines: usize,
+}
+
+struct Component {
+    cells: Vec<usize>,
+    constraints: Vec<Constraint>,
+}
+
+// Solutions of one frontier component grouped by how many mines they use:
+// `ways[k]` counts the solutions with k mines and `cell_ways[k][i]` counts
+// those of them with a mine on `cells[i]`.
+struct ComponentSolutions {
+    ways: Vec<f64>,
+    cell_ways: Vec<Vec<f64>>,
+}
+
+/// Exact mine probability of every covered cell given the visible clues and
+/// the number of mines not yet flagged. Flags are taken as mines, question
+/// marks as covered. Uncovered cells map to `None`. Returns `None` when no mine
+/// layout agrees with the clues and flags.
+pub fn calculate(visible_cells: &Grid<VisibleCell>, mines: u32) -> Option<Grid<Option<f64>>> {
+    let (constraints, revealed_mines) = get_constraints(visible_cells)?;
+    let mines = (mines as usize).checked_sub(revealed_mines)?;
+
+    let mut is_frontier = vec![false; visible_cells.len()];
+    for constraint in constraints.iter() {
+        for cell in constraint.cells.iter() {
+            is_frontier[*cell] = true;
+        }
+    }
+    let interior_cells = visible_cells
+        .iter()
+        .enumerate()
+        .filter(|(index, visible_cell)| is_unknown(visible_cell) && !is_frontier[*index])
+        .count();
+
+    let components = get_components(constraints, visible_cells.len());
+    let solutions: Vec<ComponentSolutions> = components.iter().map(solve_component).collect();
+
+    let frontier_ways = convolve(solutions.iter().map(|solution| &solution.ways));
+    let interior_weights = get_interior_weights(interior_cells, mines, frontier_ways.len());
+    let total_weight: f64 = frontier_ways
+        .iter()
+        .zip(interior_weights.iter())
+        .map(|(ways, weight)| ways * weight)
+        .sum();
+    if total_weight == 0.0 {
+        return None;
+    }
+
+    let mut probabilities = Grid::new(visible_cells.get_height(), visible_cells.get_width(), None);
+
+    for (component_index, component) in components.iter().enumerate() {
+        let other_ways = convolve(
+            solutions
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != component_index)
+                .map(|(_, solution)| &solution.ways),
+        );
+        let solution = &solutions[component_index];
+
+        for (cell_index, cell) in component.cells.iter().enumerate() {
+            let mut weight = 0.0;
+            for (component_mines, cell_ways) in solution.cell_ways.iter().enumerate() {
+                for (other_mines, ways) in other_ways.iter().enumerate() {
+                    let frontier_mines = component_mines + other_mines;
+                    if frontier_mines < interior_weights.len() {
+                        weight += cell_ways[cell_index] * ways * interior_weights[frontier_mines];
+                    }
+                }
+            }
+
+            let position = visible_cells.position(*cell);
+            probabilities.set(position.x, position.y, Some(weight / total_weight));
+        }
+    }
+
+    let mut interior_mines = 0.0;
+    for (frontier_mines, ways) in frontier_ways.iter().enumerate() {
+        if frontier_mines <= mines {
+            interior_mines +=
+                ways * interior_weights[frontier_mines] * (mines - frontier_mines) as f64;
+        }
+    }
+    let interior_probability = if interior_cells > 0 {
+        interior_mines / total_weight / interior_cells as f64
+    } else {
+        0.0
+    };
+    for (index, visible_cell) in visible_cells.iter().enumerate() {
+        let position = visible_cells.position(index);
+        if *visible_cell == VisibleCell::Flagged {
+            probabilities.set(position.x, position.y, Some(1.0));
+        } else if is_unknown(visible_cell) && !is_frontier[index] {
+            probabilities.set(position.x, position.y, Some(interior_probability));
+        }
+    }
+
+    Some(probabilities)
+}
+
+fn is_unknown(visible_cell: &VisibleCell) -> bool {
+    matches!(visible_cell, VisibleCell::Covered | VisibleCell::Questioned)
+}
+
+fn get_constraints(visible_cells: &Grid<VisibleCell>) -> Option<(Vec<Constraint>, usize)> {
+    let mut constraints = vec![];
+    let mut revealed_mines = 0;
+
+    for (index, visible_cell) in visible_cells.iter().enumerate() {
+        let clue = match visible_cell {
+            VisibleCell::Uncovered(Cell::Clue(clue)) => *clue as usize,
+            VisibleCell::Uncovered(Cell::Empty) => 0,
+            VisibleCell::Uncovered(Cell::Mine) => {
+                revealed_mines += 1;
+                continue;
+            }
+            _ => continue,
+        };
+
+        let position = visible_cells.position(index);
+        let mut cells = vec![];
+        let mut adjacent_mines = 0;
+        for adjacent in visible_cells.get_adjacent_positions(position.x, position.y) {
+            match visible_cells.get(adjacent.x, adjacent.y) {
+                VisibleCell::Uncovered(Cell::Mine) | VisibleCell::Flagged => adjacent_mines += 1,
+                VisibleCell::Uncovered(_) => {}
+                _ => cells.push(visible_cells.index(adjacent.x, adjacent.y)),
+            }
+        }
+
+        let mines = clue.checked_sub(adjacent_mines)?;
+        if !cells.is_empty() {
+            constraints.push(Constraint { cells, mines });
+        } else if mines > 0 {
+            return None;
+        }
+    }
+
+    Some((constraints, revealed_mines))
+}
+
+fn get_components(constraints: Vec<Constraint>, cells: usize) -> Vec<Component> {
+    let mut parents: Vec<usize> = (0..cells).collect();
+    for constraint in constraints.iter() {
+        for cell in constraint.cells.iter().skip(1) {
+            let first_root = find_root(&mut parents, constraint.cells[0]);
+            let root = find_root(&mut parents, *cell);
+            parents[root] = first_root;
+        }
+    }
+
+    let mut component_indices: Vec<Option<usize>> = vec![None; cells];
+    let mut components: Vec<Component> = vec![];
+    for constraint in constraints {
+        let root = find_root(&mut parents, constraint.cells[0]);
+        let component_index = *component_indices[root].get_or_insert_with(|| {
+            components.push(Component {
+                cells: vec![],
+                constraints: vec![],
+            });
+            components.len() - 1
+        });
+        let component = &mut components[component_index];
+        for cell in constraint.cells.iter() {
+            if !component.cells.contains(cell) {
+                component.cells.push(*cell);
+            }
+        }
+        component.constraints.push(constraint);
+    }
+
+    components
+}
+
+fn find_root(parents: &mut [usize], mut cell: usize) -> usize {
+    while parents[cell] != cell {
+        parents[cell] = parents[parents[cell]];
+        cell = parents[cell];
+    }
+
+    cell
+}
+
+fn solve_component(component: &Component) -> ComponentSolutions {
+    let constraints: Vec<(Vec<usize>, usize)> = component
+        .constraints
+        .iter()
+        .map(|constraint| {
+            let cells = constraint
+                .cells
+                .iter()
+                .map(|cell| {
+                    component
+                        .cells
+                        .iter()
+                        .position(|other| other == cell)
+                        .unwrap()
+                })
+                .collect();
+            (cells, constraint.mines)
+        })
+        .collect();
+
+    let mut constraints_by_cell: Vec<Vec<usize>> = vec![vec![]; component.cells.len()];
+    for (constraint_index, (cells, _)) in constraints.iter().enumerate() {
+        for cell in cells {
+            constraints_by_cell[*cell].push(constraint_index);
+        }
+    }
+
+    let mut solutions = ComponentSolutions {
+        ways: vec![0.0; component.cells.len() + 1],
+        cell_ways: vec![vec![0.0; component.cells.len()]; component.cells.len() + 1],
+    };
+    let mut assignment = vec![false; component.cells.len()];
+    let mut placed = vec![0; constraints.len()];
+    let mut remaining: Vec<usize> = constraints.iter().map(|(cells, _)| cells.len()).collect();
+    enumerate(
+        0,
+        &constraints,
+        &constraints_by_cell,
+        &mut assignment,
+        &mut placed,
+        &mut remaining,
+        &mut solutions,
+    );
+
+    solutions
+}
+
+fn enumerate(
+    cell: usize,
+    constraints: &[(Vec<usize>, usize)],
+    constraints_by_cell: &[Vec<usize>],
+    assignment: &mut Vec<bool>,
+    placed: &mut Vec<usize>,
+    remaining: &mut Vec<usize>,
+    solutions: &mut ComponentSolutions,
+) {
+    if cell == assignment.len() {
+        let mines = assignment.iter().filter(|is_mine| **is_mine).count();
+        solutions.ways[mines] += 1.0;
+        for (index, is_mine) in assignment.iter().enumerate() {
+            if *is_mine {
+                solutions.cell_ways[mines][index] += 1.0;
+            }
+        }
+        return;
+    }
+
+    for is_mine in [false, true] {
+        let fits = constraints_by_cell[cell].iter().all(|constraint| {
+            let placed_mines = placed[*constraint] + is_mine as usize;
+            let mines = constraints[*constraint].1;
+            placed_mines <= mines && placed_mines + remaining[*constraint] > mines
+        });
+        if !fits {
+            continue;
+        }
+
+        assignment[cell] = is_mine;
+        for constraint in constraints_by_cell[cell].iter() {
+            placed[*constraint] += is_mine as usize;
+            remaining[*constraint] -= 1;
+        }
+        enumerate(
+            cell + 1,
+            constraints,
+            constraints_by_cell,
+            assignment,
+            placed,
+            remaining,
+            solutions,
+        );
+        for constraint in constraints_by_cell[cell].iter() {
+            placed[*constraint] -= is_mine as usize;
+            remaining[*constraint] += 1;
+        }
+    }
+    assignment[cell] = false;
+}
+
+fn convolve<'a>(distributions: impl Iterator<Item = &'a Vec<f64>>) -> Vec<f64> {
+    let mut result = vec![1.0];
+    for distribution in distributions {
+        let mut next = vec![0.0; result.len() + distribution.len() - 1];
+        for (first_mines, first_ways) in result.iter().enumerate() {
+            for (second_mines, second_ways) in distribution.iter().enumerate() {
+                next[first_mines + second_mines] += first_ways * second_ways;
+            }
+        }
+        result = next;
+    }
+
+    result
+}
+
+// Relative number of ways to hide the leftover mines among the interior cells
+// for every possible frontier mine count, scaled so the largest one is 1.
+fn get_interior_weights(interior_cells: usize, mines: usize, frontier_counts: usize) -> Vec<f64> {
+    let mut log_factorials = vec![0.0; interior_cells + 1];
+    for n in 1..=interior_cells {
+        log_factorials[n] = log_factorials[n - 1] + (n as f64).ln();
+    }
+
+    let log_weights: Vec<Option<f64>> = (0..frontier_counts)
+        .map(|frontier_mines| {
+            let interior_mines = mines.checked_sub(frontier_mines)?;
+            if interior_mines > interior_cells {
+                return None;
+            }
+            Some(
+                log_factorials[interior_cells]
+                    - log_factorials[interior_mines]
+                    - log_factorials[interior_cells - interior_mines],
+            )
+        })
+        .collect();
+
+    let max_log_weight = log_weights
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+    log_weights
+        .iter()
+        .map(|log_weight| match log_weight {
+            Some(log_weight) => (log_weight - max_log_weight).exp(),
+            None => 0.0,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board_builder::BoardBuilder,
+        cell::{Cell, VisibleCell},
+        grid::Grid,
+        probability::calculate,
+    };
+
+    fn assert_probabilities(probabilities: &Grid<Option<f64>>, expected: &[&[Option<f64>]]) {
+        for (y, row) in expected.iter().enumerate() {
+            for (x, expected) in row.iter().enumerate() {
+                match (probabilities.get(x, y), expected) {
+                    (Some(probability), Some(expected)) => {
+                        assert!((probability - expected).abs() < 1e-9)
+                    }
+                    (None, None) => {}
+                    _ => panic!("Unexpected probability at {}, {}", x, y),
+                }
+            }
+        }
+    }
+
+    fn brute_force(visible_cells: &Grid<VisibleCell>, mines: usize) -> Vec<f64> {
+        let covered: Vec<usize> = visible_cells
+            .iter()
+            .enumerate()
+            .filter(|(_, visible_cell)| !matches!(visible_cell, VisibleCell::Uncovered(_)))
+            .map(|(index, _)| index)
+            .collect();
+        let mut layouts = 0.0;
+        let mut cell_layouts = vec![0.0; visible_cells.len()];
+
+        for mask in 0u32..(1 << covered.len()) {
+            if mask.count_ones() as usize != mines {
+                continue;
+            }
+            let mut is_mine = vec![false; visible_cells.len()];
+            for (bit, cell) in covered.iter().enumerate() {
+                is_mine[*cell] = mask & (1 << bit) != 0;
+            }
+
+            let consistent = visible_cells
+                .iter()
+                .enumerate()
+                .all(|(index, visible_cell)| {
+                    let clue = match visible_cell {
+                        VisibleCell::Uncovered(Cell::Clue(clue)) => *clue as usize,
+                        VisibleCell::Uncovered(_) => 0,
+                        _ => return true,
+                    };
+                    let position = visible_cells.position(index);
+                    visible_cells
+                        .get_adjacent_positions(position.x, position.y)
+                        .filter(|adjacent| is_mine[visible_cells.index(adjacent.x, adjacent.y)])
+                        .count()
+                        == clue
+                });
+            if consistent {
+                layouts += 1.0;
+                for cell in covered.iter() {
+                    if is_mine[*cell] {
+                        cell_layouts[*cell] += 1.0;
+                    }
+                }
+            }
+        }
+
+        cell_layouts.iter().map(|count| count / layouts).collect()
+    }
+
+    #[test]
+    fn splits_fifty_fifty() {
+        let grid = Grid::from_rows(&["##", "11"]);
+
+        let probabilities = calculate(&grid, 1).unwrap();
+        assert_probabilities(&probabilities, &[&[Some(0.5), Some(0.5)], &[None, None]]);
+    }
+
+    #[test]
+    fn resolves_one_two_one_pattern() {
+        let grid = Grid::from_rows(&["###", "121", "___"]);
+
+        let probabilities = calculate(&grid, 2).unwrap();
+        assert_probabilities(
+            &probabilities,
+            &[
+                &[Some(1.0), Some(0.0), Some(1.0)],
+                &[None, None, None],
+                &[None, None, None],
+            ],
+        );
+    }
+
+    #[test]
+    fn weights_interior_by_remaining_mines() {
+        /*
+           1##
+        */
+        let grid = Grid::from_rows(&["1##"]);
+
+        let probabilities = calculate(&grid, 1).unwrap();
+        assert_probabilities(&probabilities, &[&[None, Some(1.0), Some(0.0)]]);
+
+        let probabilities = calculate(&grid, 2).unwrap();
+        assert_probabilities(&probabilities, &[&[None, Some(1.0), Some(1.0)]]);
+    }
+
+    #[test]
+    fn spreads_remaining_mines_over_interior() {
+        /*
+           #####
+           #1###
+           #####
+        */
+        let grid = Grid::from_rows(&["#####", "#1###", "#####"]);
+
+        let probabilities = calculate(&grid, 3).unwrap();
+        let frontier = Some(1.0 / 8.0);
+        let interior = Some(2.0 / 6.0);
+        assert_probabilities(
+            &probabilities,
+            &[
+                &[frontier, frontier, frontier, interior, interior],
+                &[frontier, None, frontier, interior, interior],
+                &[frontier, frontier, frontier, interior, interior],
+            ],
+        );
+    }
+
+    #[test]
+    fn prefers_layouts_leaving_more_interior_combinations() {
+        /*
+           One mine in the middle leaves two interior layouts for the other
+           mine, two frontier mines leave only one.
+           #1#1###
+        */
+        let grid = Grid::from_rows(&["#1#1###"]);
+
+        let probabilities = calculate(&grid, 2).unwrap();
+        let third = Some(1.0 / 3.0);
+        assert_probabilities(
+            &probabilities,
+            &[&[third, None, Some(2.0 / 3.0), None, third, third, third]],
+        );
+    }
+
+    #[test]
+    fn takes_flags_as_mines() {
+        /*
+           F1#
+           11#
+        */
+        let mut grid = Grid::from_rows(&["#1#", "11#"]);
+        grid.set(0, 0, VisibleCell::Flagged);
+
+        let probabilities = calculate(&grid, 0).unwrap();
+        assert_probabilities(
+            &probabilities,
+            &[&[Some(1.0), None, Some(0.0)], &[None, None, Some(0.0)]],
+        );
+    }
+
+    #[test]
+    fn rejects_inconsistent_clues() {
+        let grid = Grid::from_rows(&["#_", "1_"]);
+
+        assert!(calculate(&grid, 1).is_none());
+    }
+
+    #[test]
+    fn matches_brute_force_on_seeded_boards() {
+        for seed in 0..20 {
+            let mut board = BoardBuilder::new(4, 5, 4).set_seed(seed).build().unwrap();
+            board.uncover_cell(0, 0);
+            let visible_cells = board.get_visible_cells();
+
+            let probabilities = calculate(visible_cells, 4).unwrap();
+            let expected = brute_force(visible_cells, 4);
+            for (index, expected) in expected.iter().enumerate() {
+                let position = visible_cells.position(index);
+                match probabilities.get(position.x, position.y) {
+                    Some(probability) => assert!((probability - expected).abs() < 1e-9),
+                    None => assert_eq!(*expected, 0.0),
+                }
+            }
+        }
+    }
+}
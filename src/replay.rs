@@ -0,0 +1,453 @@
+use crate::{
+    board::Board,
+    board_builder::BoardBuilder,
+    clock::{ManualTimeSource, TimeSource},
+    error::MinesweeperError,
+    first_click_policy::FirstClickPolicy,
+    high_scores::BoardConfig,
+    move_outcome::MoveOutcome,
+    persistence::PersistenceError,
+    position::Position,
+    state::State,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, rc::Rc, time::Duration};
+
+pub const REPLAY_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionKind {
+    Uncover,
+    Flag,
+    Chord,
+}
+
+impl ActionKind {
+    pub fn apply(
+        self,
+        board: &mut Board,
+        x: usize,
+        y: usize,
+    ) -> Result<MoveOutcome, MinesweeperError> {
+        match self {
+            ActionKind::Uncover => board.try_uncover(x, y),
+            ActionKind::Flag => board.try_flag(x, y),
+            ActionKind::Chord => board.try_chord(x, y),
+        }
+    }
+
+    fn to_char(self) -> char {
+        match self {
+            ActionKind::Uncover => 'u',
+            ActionKind::Flag => 'f',
+            ActionKind::Chord => 'c',
+        }
+    }
+
+    fn from_char(character: char) -> Option<Self> {
+        match character {
+            'u' => Some(ActionKind::Uncover),
+            'f' => Some(ActionKind::Flag),
+            'c' => Some(ActionKind::Chord),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayAction {
+    pub kind: ActionKind,
+    pub position: Position,
+    // Game time at which the action was made.
+    pub time: Duration,
+}
+
+impl ReplayAction {
+    fn encode(&self) -> String {
+        format!(
+            "{}{},{}@{}",
+            self.kind.to_char(),
+            self.position.x,
+            self.position.y,
+            self.time.as_millis()
+        )
+    }
+
+    fn decode(text: &str) -> Option<Self> {
+        let mut characters = text.chars();
+        let kind = ActionKind::from_char(characters.next()?)?;
+        let (position, millis) = characters.as_str().split_once('@')?;
+        let (x, y) = position.split_once(',')?;
+
+        Some(Self {
+            kind,
+            position: Position {
+                x: x.parse().ok()?,
+                y: y.parse().ok()?,
+            },
+            time: Duration::from_millis(millis.parse().ok()?),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub config: BoardConfig,
+    pub question_marks: bool,
+    pub first_click_policy: FirstClickPolicy,
+    pub no_guess: bool,
+    pub no_guess_attempts: u32,
+    pub actions: Vec<ReplayAction>,
+}
+
+// Actions are kept in a single string of space separated entries such as
+// "u3,4@1200": the action letter ('u'ncover, 'f'lag, 'c'hord), the cell and
+// the game time in milliseconds.
+#[derive(Serialize, Deserialize)]
+struct SavedReplay {
+    version: u32,
+    seed: u64,
+    height: usize,
+    width: usize,
+    mines: u32,
+    question_marks: bool,
+    first_click_policy: FirstClickPolicy,
+    no_guess: bool,
+    no_guess_attempts: u32,
+    actions: String,
+}
+
+impl Replay {
+    pub fn save(&self, path: &Path) -> Result<(), PersistenceError> {
+        fs::write(path, self.encode()?)?;
+
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, PersistenceError> {
+        Self::decode(&fs::read_to_string(path)?)
+    }
+
+    pub fn encode(&self) -> Result<String, PersistenceError> {
+        let saved_replay = SavedReplay {
+            version: REPLAY_FORMAT_VERSION,
+            seed: self.seed,
+            height: self.config.height,
+            width: self.config.width,
+            mines: self.config.mines,
+            question_marks: self.question_marks,
+            first_click_policy: self.first_click_policy,
+            no_guess: self.no_guess,
+            no_guess_attempts: self.no_guess_attempts,
+            actions: self
+                .actions
+                .iter()
+                .map(ReplayAction::encode)
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+
+        Ok(serde_json::to_string(&saved_replay)?)
+    }
+
+    pub fn decode(text: &str) -> Result<Self, PersistenceError> {
+        let saved_replay: SavedReplay = serde_json::from_str(text)?;
+        if saved_replay.version != REPLAY_FORMAT_VERSION {
+            return Err(PersistenceError::UnsupportedVersion(saved_replay.version));
+        }
+
+        let actions = saved_replay
+            .actions
+            .split_whitespace()
+            .map(|text| {
+                ReplayAction::decode(text)
+                    .ok_or(PersistenceError::Inconsistent("replay action is malformed"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if actions.iter().any(|action| {
+            action.position.x >= saved_replay.width || action.position.y >= saved_replay.height
+        }) {
+            return Err(PersistenceError::Inconsistent(
+                "replay action is outside the board",
+            ));
+        }
+        if actions.windows(2).any(|pair| pair[0].time > pair[1].time) {
+            return Err(PersistenceError::Inconsistent(
+                "replay actions are not in time order",
+            ));
+        }
+
+        Ok(Self {
+            seed: saved_replay.seed,
+            config: BoardConfig {
+                height: saved_replay.height,
+                width: saved_replay.width,
+                mines: saved_replay.mines,
+            },
+            question_marks: saved_replay.question_marks,
+            first_click_policy: saved_replay.first_click_policy,
+            no_guess: saved_replay.no_guess,
+            no_guess_attempts: saved_replay.no_guess_attempts,
+            actions,
+        })
+    }
+
+    pub fn get_duration(&self) -> Duration {
+        self.actions
+            .last()
+            .map(|action| action.time)
+            .unwrap_or_default()
+    }
+
+    fn build_board(&self, time_source: Rc<ManualTimeSource>) -> Result<Board, MinesweeperError> {
+        BoardBuilder::new(self.config.height, self.config.width, self.config.mines)
+            .set_seed(self.seed)
+            .set_question_marks(self.question_marks)
+            .set_first_click_policy(self.first_click_policy)
+            .set_no_guess(self.no_guess)
+            .set_no_guess_attempts(self.no_guess_attempts)
+            .set_time_source(time_source)
+            .build()
+    }
+}
+
+#[derive(Default)]
+pub struct ReplayRecorder {
+    actions: Vec<ReplayAction>,
+}
+
+impl ReplayRecorder {
+    // Every action that counts as a click is recorded, so the number of clicks
+    // on the board tells how many recorded actions led to its current state.
+    // Restarted games and undone moves are dropped on the next counted action.
+    pub fn play(
+        &mut self,
+        board: &mut Board,
+        kind: ActionKind,
+        x: usize,
+        y: usize,
+    ) -> Result<MoveOutcome, MinesweeperError> {
+        let clicks = board.get_counters().get_total_clicks() as usize;
+        let time = board.get_elapsed_time();
+
+        let result = kind.apply(board, x, y);
+        // Moves that do not count keep the actions that can still be redone.
+        if board.get_counters().get_total_clicks() as usize > clicks {
+            self.actions.truncate(clicks);
+            if self.actions.len() == clicks {
+                self.actions.push(ReplayAction {
+                    kind,
+                    position: Position { x, y },
+                    time,
+                });
+            }
+        }
+
+        result
+    }
+
+    // Games loaded from a file or not started yet cannot be replayed.
+    pub fn get_replay(&self, board: &Board) -> Option<Replay> {
+        let seed = board.seed?;
+        if *board.get_state() == State::New
+            || self.actions.len() != board.get_counters().get_total_clicks() as usize
+        {
+            return None;
+        }
+
+        Some(Replay {
+            seed,
+            config: BoardConfig::from_board(board),
+            question_marks: board.question_marks,
+            first_click_policy: board.first_click_policy,
+            no_guess: board.no_guess,
+            no_guess_attempts: board.no_guess_attempts,
+            actions: self.actions.clone(),
+        })
+    }
+}
+
+pub struct ReplayPlayer {
+    replay: Replay,
+    board: Board,
+    time_source: Rc<ManualTimeSource>,
+    next_action: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Result<Self, MinesweeperError> {
+        let time_source = Rc::new(ManualTimeSource::default());
+        let board = replay.build_board(time_source.clone())?;
+
+        Ok(Self {
+            replay,
+            board,
+            time_source,
+            next_action: 0,
+        })
+    }
+
+    pub fn restart(&mut self) {
+        self.time_source.set(Duration::ZERO);
+        self.board.reset();
+        self.board.seed = Some(self.replay.seed);
+        self.next_action = 0;
+    }
+
+    // Applies the next action, jumping the game time forward to it.
+    pub fn step(&mut self) -> Option<MoveOutcome> {
+        let action = *self.replay.actions.get(self.next_action)?;
+        self.next_action += 1;
+        if action.time > self.time_source.now() {
+            self.time_source.set(action.time);
+        }
+
+        let Position { x, y } = action.position;
+        Some(
+            action
+                .kind
+                .apply(&mut self.board, x, y)
+                .unwrap_or_else(|_| MoveOutcome::new(self.board.get_state().clone())),
+        )
+    }
+
+    // Moves the game time forward, applying every action that falls due.
+    pub fn advance(&mut self, duration: Duration) {
+        let target = (self.time_source.now() + duration).min(self.replay.get_duration());
+        while self
+            .replay
+            .actions
+            .get(self.next_action)
+            .is_some_and(|action| action.time <= target)
+        {
+            self.step();
+        }
+        self.time_source.set(target);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_action == self.replay.actions.len()
+    }
+
+    pub fn get_board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn get_replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn get_time(&self) -> Duration {
+        self.time_source.now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::Board,
+        board_builder::BoardBuilder,
+        cell::VisibleCell,
+        clock::ManualTimeSource,
+        memento::{BoardMemento, Originator},
+        persistence::PersistenceError,
+        replay::{ActionKind, Replay, ReplayPlayer, ReplayRecorder},
+        state::State,
+    };
+    use std::{rc::Rc, time::Duration};
+
+    fn play_recorded_game() -> (Board, Replay) {
+        let time_source = Rc::new(ManualTimeSource::default());
+        let mut board = BoardBuilder::new(9, 9, 10)
+            .set_time_source(time_source.clone())
+            .build()
+            .unwrap();
+        let mut recorder = ReplayRecorder::default();
+
+        recorder
+            .play(&mut board, ActionKind::Uncover, 4, 4)
+            .unwrap();
+        let memento: Box<BoardMemento> = board.save_memento();
+        time_source.advance(Duration::from_millis(300));
+        recorder.play(&mut board, ActionKind::Chord, 4, 4).unwrap();
+        board.restore_from_memento(memento);
+
+        while *board.get_state() == State::Playing {
+            time_source.advance(Duration::from_millis(700));
+            let hint = board.hint();
+            let mine = hint.mines.iter().find(|position| {
+                board.get_visible_cell(position.x, position.y) != VisibleCell::Flagged
+            });
+            let (kind, position) = match mine {
+                Some(mine) => (ActionKind::Flag, *mine),
+                None => (ActionKind::Uncover, hint.get_suggested_position().unwrap()),
+            };
+            recorder
+                .play(&mut board, kind, position.x, position.y)
+                .unwrap();
+        }
+
+        let replay = recorder.get_replay(&board).unwrap();
+        (board, replay)
+    }
+
+    #[test]
+    fn replays_recorded_game() {
+        let (board, replay) = play_recorded_game();
+        assert_eq!(
+            replay.actions.len() as u32,
+            board.get_counters().get_total_clicks()
+        );
+        assert!(replay
+            .actions
+            .iter()
+            .all(|action| action.kind != ActionKind::Chord));
+
+        let mut player = ReplayPlayer::new(replay.clone()).unwrap();
+        player.advance(Duration::from_millis(1000));
+        assert_eq!(player.get_board().get_counters().get_total_clicks(), 2);
+        while player.step().is_some() {}
+
+        assert!(player.is_finished());
+        let replayed = player.get_board();
+        assert_eq!(replayed.get_state(), board.get_state());
+        assert_eq!(replayed.get_visible_cells(), board.get_visible_cells());
+        assert_eq!(replayed.get_counters(), board.get_counters());
+        assert_eq!(replayed.get_elapsed_time(), board.get_elapsed_time());
+
+        player.restart();
+        assert_eq!(*player.get_board().get_state(), State::New);
+        player.advance(replay.get_duration());
+        assert_eq!(
+            player.get_board().get_visible_cells(),
+            board.get_visible_cells()
+        );
+    }
+
+    #[test]
+    fn encodes_and_decodes_replay() {
+        let (_, replay) = play_recorded_game();
+        let text = replay.encode().unwrap();
+        assert!(text.contains("\"actions\":\"u4,4@0 "));
+        assert_eq!(Replay::decode(&text).unwrap(), replay);
+    }
+
+    #[test]
+    fn rejects_malformed_replay() {
+        let text = r#"{"version":1,"seed":1,"height":9,"width":9,"mines":10,"question_marks":true,"first_click_policy":"SafeOpening","no_guess":false,"no_guess_attempts":1000,"actions":"u4,4@0 x1,1@5"}"#;
+        assert!(matches!(
+            Replay::decode(text),
+            Err(PersistenceError::Inconsistent(_))
+        ));
+        assert!(matches!(
+            Replay::decode(&text.replace("x1,1@5", "c100,100@5")),
+            Err(PersistenceError::Inconsistent(_))
+        ));
+        assert!(Replay::decode(&text.replace("x1,1@5", "c8,8@5")).is_ok());
+        assert!(matches!(
+            Replay::decode(&text.replace("\"version\":1", "\"version\":7")),
+            Err(PersistenceError::UnsupportedVersion(7))
+        ));
+    }
+}
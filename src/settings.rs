@@ -0,0 +1,176 @@
+use crate::{
+    board_builder::BoardBuilder, constants, difficulty::Difficulty,
+    first_click_policy::FirstClickPolicy, persistence::PersistenceError,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub difficulty: Difficulty,
+    pub theme: Theme,
+    pub question_marks: bool,
+    pub first_click_policy: FirstClickPolicy,
+    pub no_guess: bool,
+    // Ranked games cannot be undone, so their times are fair to compare.
+    pub ranked: bool,
+    pub cell_size: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            difficulty: Difficulty::from_dimensions(
+                constants::DEFAULT_BOARD_HEIGHT,
+                constants::DEFAULT_BOARD_WIDTH,
+                constants::DEFAULT_BOARD_MINES,
+            ),
+            theme: Theme::default(),
+            question_marks: true,
+            first_click_policy: FirstClickPolicy::default(),
+            no_guess: false,
+            ranked: false,
+            cell_size: constants::CELL_SIZE,
+        }
+    }
+}
+
+impl Settings {
+    pub fn get_default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|directory| directory.join("minesweeper").join("settings.json"))
+    }
+
+    // Missing or corrupt files give the defaults.
+    pub fn load_or_default() -> Self {
+        Self::get_default_path()
+            .and_then(|path| Self::load(&path).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn load(path: &Path) -> Result<Self, PersistenceError> {
+        let mut settings: Settings = serde_json::from_str(&fs::read_to_string(path)?)?;
+        settings.sanitize();
+
+        Ok(settings)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), PersistenceError> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    pub fn save_to_default_path(&self) -> Result<(), PersistenceError> {
+        match Self::get_default_path() {
+            Some(path) => self.save(&path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get_board_builder(&self) -> BoardBuilder {
+        BoardBuilder::from_difficulty(self.difficulty)
+            .set_question_marks(self.question_marks)
+            .set_first_click_policy(self.first_click_policy)
+            .set_no_guess(self.no_guess)
+    }
+
+    // Values out of range fall back one by one so a single bad entry keeps the rest.
+    fn sanitize(&mut self) {
+        let defaults = Settings::default();
+        if !(constants::MIN_CELL_SIZE..=constants::MAX_CELL_SIZE).contains(&self.cell_size) {
+            self.cell_size = defaults.cell_size;
+        }
+        if self.get_board_builder().build().is_err() {
+            self.difficulty = defaults.difficulty;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        difficulty::Difficulty,
+        first_click_policy::FirstClickPolicy,
+        persistence::PersistenceError,
+        settings::{Settings, Theme},
+    };
+    use std::{env, fs, path::PathBuf};
+
+    fn get_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!(
+            "minesweeper_settings_{}_{}.json",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn saves_and_loads_settings() {
+        let path = get_path("round_trip");
+        let settings = Settings {
+            difficulty: Difficulty::Custom {
+                height: 20,
+                width: 24,
+                mines: 90,
+            },
+            theme: Theme::Dark,
+            question_marks: false,
+            first_click_policy: FirstClickPolicy::SafeCell,
+            no_guess: true,
+            ranked: true,
+            cell_size: 24.0,
+        };
+
+        settings.save(&path).unwrap();
+        let loaded = Settings::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, settings);
+    }
+
+    #[test]
+    fn falls_back_on_invalid_values() {
+        let path = get_path("invalid");
+        fs::write(
+            &path,
+            r#"{"difficulty": {"Custom": {"height": 3, "width": 3, "mines": 20}}, "cell_size": 0.0, "theme": "Dark"}"#,
+        )
+        .unwrap();
+        let loaded = Settings::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let defaults = Settings::default();
+        assert_eq!(loaded.difficulty, defaults.difficulty);
+        assert_eq!(loaded.cell_size, defaults.cell_size);
+        assert_eq!(loaded.theme, Theme::Dark);
+        assert!(loaded.question_marks);
+    }
+
+    #[test]
+    fn reports_corrupt_file() {
+        let path = get_path("corrupt");
+        fs::write(&path, "{ not json").unwrap();
+        let result = Settings::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(PersistenceError::Format(_))));
+        assert!(matches!(
+            Settings::load(&get_path("missing")),
+            Err(PersistenceError::Io(_))
+        ));
+    }
+}
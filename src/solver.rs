@@ -0,0 +1,340 @@
+use crate::cell::{Cell, VisibleCell};
+use crate::grid::Grid;
+use crate::position::Position;
+use crate::probability;
+
+pub struct Deductions {
+    pub safe: Vec<Position>,
+    pub mines: Vec<Position>,
+}
+
+pub struct Hint {
+    pub safe: Vec<Position>,
+    pub mines: Vec<Position>,
+    pub probabilities: Vec<(Position, f64)>,
+}
+
+impl Hint {
+    pub fn get_suggested_position(&self) -> Option<Position> {
+        if let Some(position) = self.safe.first() {
+            return Some(*position);
+        }
+
+        self.probabilities
+            .iter()
+            .min_by(|(_, first), (_, second)| first.total_cmp(second))
+            .map(|(position, _)| *position)
+    }
+}
+
+struct Constraint {
+    cells: Vec<usize>,
+    mines: usize,
+}
+
+/// Finds covered cells that are certainly safe or certainly mines, looking only
+/// at what the player can see. Flags and question marks are treated as covered.
+pub fn deduce(visible_cells: &Grid<VisibleCell>, total_mines: u32) -> Deductions {
+    let known = get_known_cells(visible_cells, total_mines);
+    get_deductions(visible_cells, &known)
+}
+
+/// Like `deduce`, but when nothing is certain it also gives the exact mine
+/// probability of every covered cell so the safest guess can be suggested.
+pub fn hint(visible_cells: &Grid<VisibleCell>, total_mines: u32) -> Hint {
+    let known = get_known_cells(visible_cells, total_mines);
+    let Deductions { safe, mines } = get_deductions(visible_cells, &known);
+    let probabilities = if safe.is_empty() {
+        get_probabilities(visible_cells, total_mines)
+    } else {
+        vec![]
+    };
+
+    Hint {
+        safe,
+        mines,
+        probabilities,
+    }
+}
+
+fn get_known_cells(visible_cells: &Grid<VisibleCell>, total_mines: u32) -> Vec<Option<bool>> {
+    let mut known: Vec<Option<bool>> = visible_cells
+        .iter()
+        .map(|visible_cell| match visible_cell {
+            VisibleCell::Uncovered(Cell::Mine) => Some(true),
+            VisibleCell::Uncovered(_) => Some(false),
+            _ => None,
+        })
+        .collect();
+
+    while apply_rules(visible_cells, total_mines as usize, &mut known) {}
+
+    known
+}
+
+fn get_deductions(visible_cells: &Grid<VisibleCell>, known: &[Option<bool>]) -> Deductions {
+    let mut deductions = Deductions {
+        safe: vec![],
+        mines: vec![],
+    };
+    for (index, visible_cell) in visible_cells.iter().enumerate() {
+        if matches!(visible_cell, VisibleCell::Uncovered(_)) {
+            continue;
+        }
+        match known[index] {
+            Some(true) => deductions.mines.push(visible_cells.position(index)),
+            Some(false) => deductions.safe.push(visible_cells.position(index)),
+            None => {}
+        }
+    }
+
+    deductions
+}
+
+// The exact calculation takes flags as mines, but the player's flags may be
+// wrong, so the hint only trusts the clues.
+fn get_probabilities(visible_cells: &Grid<VisibleCell>, total_mines: u32) -> Vec<(Position, f64)> {
+    let mut unflagged_cells = visible_cells.clone();
+    for index in 0..visible_cells.len() {
+        let position = visible_cells.position(index);
+        if *visible_cells.get(position.x, position.y) == VisibleCell::Flagged {
+            unflagged_cells.set(position.x, position.y, VisibleCell::Covered);
+        }
+    }
+
+    let probabilities = match probability::calculate(&unflagged_cells, total_mines) {
+        Some(probabilities) => probabilities,
+        None => return vec![],
+    };
+    probabilities
+        .iter()
+        .enumerate()
+        .filter_map(|(index, probability)| {
+            probability.map(|probability| (probabilities.position(index), probability))
+        })
+        .collect()
+}
+
+fn apply_rules(
+    visible_cells: &Grid<VisibleCell>,
+    total_mines: usize,
+    known: &mut [Option<bool>],
+) -> bool {
+    let constraints = get_constraints(visible_cells, known);
+    let mut changed = false;
+
+    for constraint in constraints.iter() {
+        if constraint.mines == 0 {
+            changed |= mark(known, &constraint.cells, false);
+        } else if constraint.mines == constraint.cells.len() {
+            changed |= mark(known, &constraint.cells, true);
+        }
+    }
+    if changed {
+        return true;
+    }
+
+    let mut constraints_by_cell: Vec<Vec<usize>> = vec![vec![]; known.len()];
+    for (constraint_index, constraint) in constraints.iter().enumerate() {
+        for cell in constraint.cells.iter() {
+            constraints_by_cell[*cell].push(constraint_index);
+        }
+    }
+
+    for subset in constraints.iter() {
+        for superset_index in constraints_by_cell[subset.cells[0]].iter() {
+            let superset = &constraints[*superset_index];
+            if superset.cells.len() <= subset.cells.len()
+                || !subset
+                    .cells
+                    .iter()
+                    .all(|cell| superset.cells.binary_search(cell).is_ok())
+            {
+                continue;
+            }
+
+            let mines = match superset.mines.checked_sub(subset.mines) {
+                Some(mines) => mines,
+                None => continue,
+            };
+            let difference: Vec<usize> = superset
+                .cells
+                .iter()
+                .filter(|cell| subset.cells.binary_search(cell).is_err())
+                .copied()
+                .collect();
+            if mines == 0 {
+                changed |= mark(known, &difference, false);
+            } else if mines == difference.len() {
+                changed |= mark(known, &difference, true);
+            }
+        }
+    }
+    if changed {
+        return true;
+    }
+
+    let unknown: Vec<usize> = (0..known.len())
+        .filter(|index| known[*index].is_none())
+        .collect();
+    let found_mines = known.iter().filter(|cell| **cell == Some(true)).count();
+    let remaining_mines = total_mines.saturating_sub(found_mines);
+    if remaining_mines == 0 {
+        changed |= mark(known, &unknown, false);
+    } else if remaining_mines == unknown.len() {
+        changed |= mark(known, &unknown, true);
+    }
+
+    changed
+}
+
+fn get_constraints(visible_cells: &Grid<VisibleCell>, known: &[Option<bool>]) -> Vec<Constraint> {
+    let mut constraints = vec![];
+
+    for (index, visible_cell) in visible_cells.iter().enumerate() {
+        let clue = match visible_cell {
+            VisibleCell::Uncovered(Cell::Clue(clue)) => *clue as usize,
+            VisibleCell::Uncovered(Cell::Empty) => 0,
+            _ => continue,
+        };
+
+        let position = visible_cells.position(index);
+        let mut cells = vec![];
+        let mut found_mines = 0;
+        for adjacent in visible_cells.get_adjacent_positions(position.x, position.y) {
+            let adjacent_index = visible_cells.index(adjacent.x, adjacent.y);
+            match known[adjacent_index] {
+                Some(true) => found_mines += 1,
+                Some(false) => {}
+                None => cells.push(adjacent_index),
+            }
+        }
+
+        if !cells.is_empty() && found_mines <= clue {
+            cells.sort_unstable();
+            constraints.push(Constraint {
+                cells,
+                mines: clue - found_mines,
+            });
+        }
+    }
+
+    constraints
+}
+
+fn mark(known: &mut [Option<bool>], cells: &[usize], is_mine: bool) -> bool {
+    let mut changed = false;
+    for cell in cells {
+        if known[*cell].is_none() {
+            known[*cell] = Some(is_mine);
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cell::VisibleCell,
+        grid::Grid,
+        solver::{deduce, hint},
+    };
+
+    #[test]
+    fn finds_mine_and_safe_cell_from_single_clues() {
+        /*
+           _1#
+           _1#
+           _11
+        */
+        let grid = Grid::from_rows(&["_1#", "_1#", "_11"]);
+
+        let deductions = deduce(&grid, 1);
+        assert_eq!(deductions.mines.len(), 1);
+        assert_eq!((deductions.mines[0].x, deductions.mines[0].y), (2, 1));
+        assert_eq!(deductions.safe.len(), 1);
+        assert_eq!((deductions.safe[0].x, deductions.safe[0].y), (2, 0));
+    }
+
+    #[test]
+    fn finds_safe_cell_from_subset_rule() {
+        /*
+           ###
+           121
+           ___
+        */
+        let grid = Grid::from_rows(&["###", "121", "___"]);
+
+        let deductions = deduce(&grid, 3);
+        assert_eq!(deductions.safe.len(), 1);
+        assert_eq!((deductions.safe[0].x, deductions.safe[0].y), (1, 0));
+        assert_eq!(deductions.mines.len(), 2);
+    }
+
+    #[test]
+    fn does_not_guess_on_fifty_fifty() {
+        /*
+           ##
+           11
+        */
+        let grid = Grid::from_rows(&["##", "11"]);
+
+        let deductions = deduce(&grid, 1);
+        assert!(deductions.safe.is_empty());
+        assert!(deductions.mines.is_empty());
+    }
+
+    #[test]
+    fn estimates_probabilities_when_stuck() {
+        /*
+           ##
+           11
+        */
+        let grid = Grid::from_rows(&["##", "11"]);
+
+        let hint = hint(&grid, 1);
+        assert!(hint.safe.is_empty());
+        assert_eq!(hint.probabilities.len(), 2);
+        assert!(hint
+            .probabilities
+            .iter()
+            .all(|(_, probability)| (probability - 0.5).abs() < 1e-9));
+        assert!(hint.get_suggested_position().is_some());
+    }
+
+    #[test]
+    fn gives_exact_probabilities_ignoring_flags() {
+        /*
+           1F#
+           ###
+           ###
+        */
+        let mut grid = Grid::from_rows(&["1##", "###", "###"]);
+        grid.set(1, 0, VisibleCell::Flagged);
+
+        let hint = hint(&grid, 2);
+        assert_eq!(hint.probabilities.len(), 8);
+        for (position, probability) in hint.probabilities.iter() {
+            let expected = if position.x <= 1 && position.y <= 1 {
+                1.0 / 3.0
+            } else {
+                0.2
+            };
+            assert!((probability - expected).abs() < 1e-9);
+        }
+        let position = hint.get_suggested_position().unwrap();
+        assert!(position.x == 2 || position.y == 2);
+    }
+
+    #[test]
+    fn suggests_certain_safe_cell_first() {
+        let grid = Grid::from_rows(&["_1#", "_1#", "_11"]);
+
+        let hint = hint(&grid, 1);
+        let position = hint.get_suggested_position().unwrap();
+        assert_eq!((position.x, position.y), (2, 0));
+        assert!(hint.probabilities.is_empty());
+    }
+}